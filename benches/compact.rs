@@ -0,0 +1,66 @@
+use aeski::color::ansi_24_bit::Ansi24Bit;
+use aeski::color::ansi_8_bit::Ansi8Bit;
+use aeski::color::compact::CompactColor;
+use aeski::font::Font;
+use aeski::image::AsciiImage;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use image::imageops::FilterType;
+use image::ImageFormat::Jpeg;
+use image::ImageReader;
+use std::hint::black_box;
+use std::io::Cursor;
+
+const GRADIENT: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+const COVERAGE: f64 = 0.3;
+const ASPECT_RATIO: f64 = 1.0 / 2.0;
+
+/// The dimensions of a 4K source image.
+const SOURCE_WIDTH: u32 = 3840;
+const SOURCE_HEIGHT: u32 = 2160;
+/// One character per 8×8 block of the source.
+const WIDTH: u32 = SOURCE_WIDTH / 8;
+
+fn compact_4k(criterion: &mut Criterion) {
+    let data = Cursor::new(include_bytes!("Mona Lisa.jpg"));
+    let image = ImageReader::with_format(data, Jpeg)
+        .decode()
+        .unwrap()
+        .resize_exact(SOURCE_WIDTH, SOURCE_HEIGHT, FilterType::Triangle);
+    let font = Font::new_float(GRADIENT, COVERAGE, ASPECT_RATIO).unwrap();
+
+    let ascii_8_bit = AsciiImage::<Ansi8Bit>::from_image_with_width(&image, &font, WIDTH);
+    let ascii_24_bit = AsciiImage::<Ansi24Bit>::from_image_with_width(&image, &font, WIDTH);
+
+    bench_memory(criterion, "8 bit", &ascii_8_bit);
+    bench_memory(criterion, "24 bit", &ascii_24_bit);
+
+    criterion.bench_function("8 bit pack", |bencher| {
+        bencher.iter(|| black_box(&ascii_8_bit).pack())
+    });
+
+    let packed_8_bit = ascii_8_bit.pack();
+
+    criterion.bench_function("8 bit unpacked format", |bencher| {
+        bencher.iter(|| black_box(&ascii_8_bit).to_string())
+    });
+
+    criterion.bench_function("8 bit packed format", |bencher| {
+        bencher.iter(|| black_box(&packed_8_bit).to_string())
+    });
+}
+
+/// Clones the cells with and without packing them.
+/// How much memory packing saves is checked by the tests.
+fn bench_memory<C: CompactColor>(criterion: &mut Criterion, name: &str, ascii: &AsciiImage<C>) {
+    let packed = ascii.pack();
+    let mut group = criterion.benchmark_group(format!("{name} clone"));
+    group.throughput(Throughput::Elements(ascii.cells().len() as u64));
+
+    group.bench_function("unpacked", |bencher| bencher.iter(|| black_box(ascii).clone()));
+    group.bench_function("packed", |bencher| bencher.iter(|| black_box(&packed).clone()));
+
+    group.finish();
+}
+
+criterion_group!(benches, compact_4k);
+criterion_main!(benches);
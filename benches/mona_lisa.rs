@@ -1,4 +1,5 @@
 use aeski::color::ansi_4_bit::Ansi4Bit;
+use aeski::conversion::Conversion;
 use aeski::font::Font;
 use aeski::image::AsciiImage;
 use criterion::{criterion_group, criterion_main, Criterion};
@@ -29,6 +30,24 @@ fn mona_lisa_4_bit(criterion: &mut Criterion) {
         })
     });
 
+    let linear_light = Conversion {
+        linear_light: true,
+        ..Conversion::default()
+    };
+    let height = AsciiImage::<Ansi4Bit>::from_image_with_width(&image, &font, WIDTH).height();
+
+    criterion.bench_function("4 bit linear light", |bencher| {
+        bencher.iter(|| {
+            AsciiImage::<Ansi4Bit>::from_image_with_conversion(
+                black_box(&image),
+                black_box(&font),
+                black_box(&linear_light),
+                black_box(WIDTH),
+                black_box(height),
+            )
+        })
+    });
+
     criterion.bench_function("8 bit", |bencher| {
         bencher.iter(|| {
             AsciiImage::<Ansi8Bit>::from_image_with_width(
@@ -0,0 +1,346 @@
+//! Export to and import from the formats of ANSI art editors such as PabloDraw and Moebius:
+//! `.ANS` files, code page 437 text with ansi escape codes, and XBin.
+//!
+//! Both formats are limited to the 16 colors of the VGA text mode,
+//! so `Ansi4Bit` cells are stored as they are and other colors are approximated.
+//! Bright foregrounds are drawn as bold, bright backgrounds require iCE colors (see [`Sauce`])
+//! and characters outside of code page 437 are replaced with `?`,
+//! in `.ANS` files along with the ones drawn for control characters.
+
+mod sauce;
+mod xbin;
+
+pub use sauce::Sauce;
+
+use crate::ansi_art::sauce::{Record, ANSI, CHARACTER, END_OF_FILE};
+use crate::cell::{AsciiCell, Attributes, Foreground};
+use crate::color::ansi_3_bit::Ansi3Bit;
+use crate::color::ansi_4_bit::Ansi4Bit;
+use crate::color::compact::CompactColor;
+use crate::color::Color;
+use crate::cp437;
+use crate::image::AsciiImage;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// The width of `.ANS` files without a SAUCE record.
+const DEFAULT_WIDTH: u32 = 80;
+
+/// Images read from `.ANS` files are cut off after this many rows.
+const MAX_HEIGHT: u32 = u16::MAX as u32;
+
+const ESCAPE: u8 = 0x1B;
+
+const RESET: u32 = 0;
+const BOLD: u32 = 1;
+const BLINK: u32 = 5;
+const NORMAL_INTENSITY: u32 = 22;
+const NOT_BLINKING: u32 = 25;
+const FOREGROUND: u32 = 30;
+const DEFAULT_FOREGROUND: u32 = 39;
+const BACKGROUND: u32 = 40;
+const DEFAULT_BACKGROUND: u32 = 49;
+
+/// An error that occurred while exporting or importing ANSI art.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum AnsiArtError {
+    /// The image is wider or higher than the format allows.
+    TooLarge,
+    /// The data doesn't start with the XBin header.
+    NotXBin,
+    /// The file ended early.
+    Truncated,
+}
+
+impl Display for AnsiArtError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnsiArtError::TooLarge => write!(f, "the image is too large"),
+            AnsiArtError::NotXBin => write!(f, "not an XBin file"),
+            AnsiArtError::Truncated => write!(f, "the file ended early"),
+        }
+    }
+}
+
+impl Error for AnsiArtError {}
+
+/// A cell as drawn by the VGA text mode.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+struct DosCell {
+    character: u8,
+    foreground: Ansi4Bit,
+    background: Ansi4Bit,
+}
+
+impl DosCell {
+    /// Converts `cell`, encoding its character using `encode`.
+    fn new<C: Color>(cell: &AsciiCell<C>, ice_colors: bool, encode: fn(char) -> Option<u8>) -> DosCell {
+        let background = Ansi4Bit::from_rgb(cell.background.to_rgb());
+
+        let (character, foreground) = match cell.foreground {
+            Some(foreground) => (
+                encode(foreground.character).unwrap_or(b'?'),
+                Ansi4Bit::from_rgb(foreground.color.to_rgb_with_attributes(cell.attributes)),
+            ),
+            None => (b' ', background),
+        };
+
+        let (foreground, mut background) = if cell.attributes.reverse {
+            (background, foreground)
+        } else {
+            (foreground, background)
+        };
+
+        background.is_bright &= ice_colors;
+
+        DosCell {
+            character,
+            foreground,
+            background,
+        }
+    }
+
+    fn to_cell(self) -> AsciiCell<Ansi4Bit> {
+        let character = cp437::decode(self.character);
+
+        AsciiCell {
+            background: self.background,
+            foreground: (character != ' ').then_some(Foreground {
+                color: self.foreground,
+                character,
+            }),
+            attributes: Attributes::NONE,
+        }
+    }
+}
+
+impl<C: Color> AsciiImage<C> {
+    /// Encodes the image as an `.ANS` file with a SAUCE record.
+    #[must_use]
+    pub fn to_ans(&self, sauce: &Sauce) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut previous = None;
+
+        for (y, row) in self.rows().enumerate() {
+            if y != 0 {
+                bytes.extend(b"\r\n");
+            }
+
+            for cell in row {
+                let cell = DosCell::new(cell, sauce.ice_colors, cp437::encode_printable);
+                let colors = (cell.foreground, cell.background);
+
+                if previous != Some(colors) {
+                    write_colors(&mut bytes, cell.foreground, cell.background);
+                    previous = Some(colors);
+                }
+
+                bytes.push(cell.character);
+            }
+        }
+
+        bytes.extend([ESCAPE, b'[', b'0', b'm']);
+
+        let width = self.width().try_into().unwrap_or(u16::MAX);
+        let height = self.height().try_into().unwrap_or(u16::MAX);
+        sauce.write(&mut bytes, CHARACTER, ANSI, width, height);
+
+        bytes
+    }
+}
+
+/// Writes the escape code which resets the graphic rendition and selects the colors.
+fn write_colors(bytes: &mut Vec<u8>, foreground: Ansi4Bit, background: Ansi4Bit) {
+    let mut parameters = vec![RESET];
+
+    if foreground.is_bright {
+        parameters.push(BOLD);
+    }
+    if background.is_bright {
+        parameters.push(BLINK);
+    }
+    parameters.push(FOREGROUND + foreground.color as u32);
+    parameters.push(BACKGROUND + background.color as u32);
+
+    let parameters: Vec<String> = parameters.iter().map(u32::to_string).collect();
+
+    bytes.extend([ESCAPE, b'[']);
+    bytes.extend(parameters.join(";").as_bytes());
+    bytes.push(b'm');
+}
+
+/// The state of the terminal while reading an `.ANS` file.
+struct AnsiReader {
+    width: u32,
+    ice_colors: bool,
+    rows: Vec<Vec<AsciiCell<Ansi4Bit>>>,
+    x: u32,
+    y: u32,
+    foreground: Ansi3Bit,
+    background: Ansi3Bit,
+    bold: bool,
+    blink: bool,
+}
+
+impl AnsiReader {
+    fn write(&mut self, character: u8) {
+        // The cursor wraps lazily, so that a line break after a full row doesn't skip a row.
+        if self.width <= self.x {
+            self.x = 0;
+            self.y += 1;
+        }
+
+        if MAX_HEIGHT <= self.y {
+            return;
+        }
+
+        let cell = DosCell {
+            character,
+            foreground: Ansi4Bit {
+                is_bright: self.bold,
+                color: self.foreground,
+            },
+            background: Ansi4Bit {
+                is_bright: self.blink && self.ice_colors,
+                color: self.background,
+            },
+        };
+
+        let y = self.y as usize;
+        if self.rows.len() <= y {
+            self.rows.resize(y + 1, Vec::new());
+        }
+
+        let row = &mut self.rows[y];
+        row.resize(row.len().max(self.width as usize), AsciiCell::default());
+        row[self.x as usize] = cell.to_cell();
+
+        self.x += 1;
+    }
+
+    fn select_graphic_rendition(&mut self, parameters: &[Option<u32>]) {
+        // An empty parameter, including `ESC[m`, resets as well.
+        for parameter in parameters {
+            match parameter.unwrap_or(RESET) {
+                RESET => {
+                    self.foreground = Ansi3Bit::White;
+                    self.background = Ansi3Bit::Black;
+                    self.bold = false;
+                    self.blink = false;
+                }
+                BOLD => self.bold = true,
+                BLINK => self.blink = true,
+                NORMAL_INTENSITY => self.bold = false,
+                NOT_BLINKING => self.blink = false,
+                DEFAULT_FOREGROUND => self.foreground = Ansi3Bit::White,
+                DEFAULT_BACKGROUND => self.background = Ansi3Bit::Black,
+                color @ FOREGROUND..DEFAULT_FOREGROUND => {
+                    self.foreground = Ansi3Bit::expand((color - FOREGROUND) as u8);
+                }
+                color @ BACKGROUND..DEFAULT_BACKGROUND => {
+                    self.background = Ansi3Bit::expand((color - BACKGROUND) as u8);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Executes the control sequence ending in `command`.
+    fn control_sequence(&mut self, parameters: &[Option<u32>], command: u8) {
+        let count = parameters.first().copied().flatten().unwrap_or(1);
+        let last_column = self.width - 1;
+
+        match command {
+            b'm' => self.select_graphic_rendition(parameters),
+            b'A' => self.y = self.y.saturating_sub(count),
+            b'B' => self.y = self.y.saturating_add(count),
+            b'C' => self.x = self.x.saturating_add(count).min(last_column),
+            b'D' => self.x = self.x.saturating_sub(count).min(last_column),
+            b'H' | b'f' => {
+                let position = |index: usize| parameters.get(index).copied().flatten().unwrap_or(1).max(1) - 1;
+                self.y = position(0);
+                self.x = position(1).min(last_column);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl AsciiImage<Ansi4Bit> {
+    /// Decodes an `.ANS` file.
+    ///
+    /// Only the escape codes for colors and for moving the cursor are interpreted.
+    /// The width is taken from the SAUCE record and is 80 if there is none.
+    #[must_use]
+    pub fn from_ans(file: &[u8]) -> Self {
+        let record = Record::read(file).filter(|record| record.data_type == CHARACTER);
+
+        let width = record
+            .as_ref()
+            .map(|record| record.width as u32)
+            .filter(|&width| width != 0)
+            .unwrap_or(DEFAULT_WIDTH);
+        let minimum_height = record.as_ref().map_or(0, |record| record.height as usize);
+
+        let mut reader = AnsiReader {
+            width,
+            ice_colors: record.is_some_and(|record| record.sauce.ice_colors),
+            rows: Vec::new(),
+            x: 0,
+            y: 0,
+            foreground: Ansi3Bit::White,
+            background: Ansi3Bit::Black,
+            bold: false,
+            blink: false,
+        };
+
+        let data = file.split(|&byte| byte == END_OF_FILE).next().unwrap_or_default();
+        let mut index = 0;
+
+        while let Some(&byte) = data.get(index) {
+            index += 1;
+
+            match byte {
+                ESCAPE if data.get(index) == Some(&b'[') => {
+                    let sequence = &data[index + 1..];
+                    let Some(length) = sequence.iter().position(|byte| (0x40..=0x7E).contains(byte)) else {
+                        break;
+                    };
+
+                    let parameters: Vec<Option<u32>> = sequence[..length]
+                        .split(|&byte| byte == b';')
+                        .map(|parameter| std::str::from_utf8(parameter).ok()?.parse().ok())
+                        .collect();
+
+                    reader.control_sequence(&parameters, sequence[length]);
+                    index += length + 2;
+                }
+                b'\r' => reader.x = 0,
+                b'\n' => {
+                    reader.x = 0;
+                    reader.y += 1;
+                }
+                character => reader.write(character),
+            }
+        }
+
+        let mut rows = reader.rows;
+        if rows.len() < minimum_height {
+            rows.resize(minimum_height, Vec::new());
+        }
+
+        // An empty image is stored with a width of 0, which SAUCE reads as the default width.
+        if rows.is_empty() {
+            return AsciiImage::new();
+        }
+
+        let height = rows.len() as u32;
+        let cells = rows.into_iter().flat_map(|mut row| {
+            row.resize(width as usize, AsciiCell::default());
+            row
+        });
+
+        // Every row was resized to the width.
+        AsciiImage::from_cells(cells, width, height).unwrap_or_default()
+    }
+}
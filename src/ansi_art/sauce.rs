@@ -0,0 +1,129 @@
+use crate::cp437;
+
+const ID: &[u8; 7] = b"SAUCE00";
+const LENGTH: usize = 128;
+
+/// Marks the end of the art, so that the SAUCE record isn't displayed.
+pub(super) const END_OF_FILE: u8 = 0x1A;
+
+/// The data type of character based files, such as `.ANS`.
+pub(super) const CHARACTER: u8 = 1;
+/// The file type of `.ANS` files, given the character data type.
+pub(super) const ANSI: u8 = 1;
+/// The data type of XBin files, which have no file types.
+pub(super) const XBIN: u8 = 6;
+
+/// Set in the flags if bright backgrounds are used instead of blinking.
+const ICE_COLORS: u8 = 1;
+
+/// The metadata stored in the SAUCE record at the end of an ANSI art file.
+///
+/// Text is stored in code page 437 and cut off at the length the record allows,
+/// 35 characters for the title, 20 for the author and group, and 22 for the font.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Sauce {
+    pub title: String,
+    pub author: String,
+    pub group: String,
+    /// The creation date as `CCYYMMDD`.
+    pub date: String,
+    /// The name of the font the art is meant to be viewed with, e.g. `"IBM VGA"`.
+    pub font: String,
+    /// Whether the blink attribute selects bright backgrounds instead.
+    /// Without iCE colors, bright backgrounds are darkened when exporting.
+    pub ice_colors: bool,
+}
+
+impl Default for Sauce {
+    fn default() -> Self {
+        Sauce {
+            title: String::new(),
+            author: String::new(),
+            group: String::new(),
+            date: String::new(),
+            font: "IBM VGA".to_string(),
+            ice_colors: false,
+        }
+    }
+}
+
+/// A SAUCE record along with the fields describing the data.
+pub(super) struct Record {
+    pub(super) sauce: Sauce,
+    pub(super) data_type: u8,
+    /// The width in characters for character based files.
+    pub(super) width: u16,
+    /// The height in characters for character based files.
+    pub(super) height: u16,
+}
+
+impl Sauce {
+    /// Reads the SAUCE record at the end of `file`, if there is one.
+    #[must_use]
+    pub fn read(file: &[u8]) -> Option<Sauce> {
+        Record::read(file).map(|record| record.sauce)
+    }
+
+    /// Appends the end of file marker and the record describing the data already in `bytes`.
+    pub(super) fn write(&self, bytes: &mut Vec<u8>, data_type: u8, file_type: u8, width: u16, height: u16) {
+        let data_length = bytes.len().try_into().unwrap_or(u32::MAX);
+
+        bytes.push(END_OF_FILE);
+        bytes.extend(ID);
+        write_text(bytes, &self.title, 35, b' ');
+        write_text(bytes, &self.author, 20, b' ');
+        write_text(bytes, &self.group, 20, b' ');
+        write_text(bytes, &self.date, 8, b' ');
+        bytes.extend(u32::to_le_bytes(data_length));
+        bytes.extend([data_type, file_type]);
+        bytes.extend(width.to_le_bytes());
+        bytes.extend(height.to_le_bytes());
+        // The other type dependent info and the number of comments
+        bytes.extend([0; 5]);
+        bytes.push(if self.ice_colors { ICE_COLORS } else { 0 });
+        write_text(bytes, &self.font, 22, 0);
+    }
+}
+
+impl Record {
+    pub(super) fn read(file: &[u8]) -> Option<Record> {
+        let record = file.get(file.len().checked_sub(LENGTH)?..)?;
+
+        if !record.starts_with(ID) {
+            return None;
+        }
+
+        let u16_at = |index: usize| u16::from_le_bytes([record[index], record[index + 1]]);
+
+        Some(Record {
+            sauce: Sauce {
+                title: read_text(&record[7..42]),
+                author: read_text(&record[42..62]),
+                group: read_text(&record[62..82]),
+                date: read_text(&record[82..90]),
+                font: read_text(&record[106..128]),
+                ice_colors: record[105] & ICE_COLORS != 0,
+            },
+            data_type: record[94],
+            width: u16_at(96),
+            height: u16_at(98),
+        })
+    }
+}
+
+/// Writes `text` in code page 437, cut off or padded with `padding` to `length` bytes.
+fn write_text(bytes: &mut Vec<u8>, text: &str, length: usize, padding: u8) {
+    let encoded = text
+        .chars()
+        .map(|character| cp437::encode(character).unwrap_or(b'?'))
+        .chain(std::iter::repeat(padding))
+        .take(length);
+
+    bytes.extend(encoded);
+}
+
+/// Reads text padded with spaces or null bytes.
+fn read_text(bytes: &[u8]) -> String {
+    let text: String = bytes.iter().map(|&byte| cp437::decode(byte)).collect();
+    text.trim_end().to_string()
+}
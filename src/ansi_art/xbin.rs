@@ -0,0 +1,164 @@
+use crate::ansi_art::sauce::{Sauce, XBIN};
+use crate::ansi_art::{AnsiArtError, DosCell};
+use crate::color::ansi_4_bit::Ansi4Bit;
+use crate::color::compact::CompactColor;
+use crate::color::Color;
+use crate::cp437;
+use crate::image::AsciiImage;
+
+const ID: &[u8; 5] = b"XBIN\x1A";
+const FONT_HEIGHT: u8 = 16;
+const PALETTE_LENGTH: usize = 48;
+
+/// Set in the flags if the file contains a palette.
+const PALETTE: u8 = 1;
+/// Set in the flags if the file contains a font.
+const FONT: u8 = 1 << 1;
+/// Set in the flags if the cells are run-length encoded.
+const COMPRESSED: u8 = 1 << 2;
+/// Set in the flags if the high bit of the attributes selects bright backgrounds instead of blinking.
+const NON_BLINK: u8 = 1 << 3;
+/// Set in the flags if the font has 512 instead of 256 characters.
+const FIVE_HUNDRED_TWELVE_CHARACTERS: u8 = 1 << 4;
+
+/// The VGA text mode attribute byte of the colors.
+///
+/// VGA orders the colors blue, green, red from the lowest bit,
+/// the reverse of ansi, but is otherwise laid out like the compact representation.
+fn attribute(foreground: Ansi4Bit, background: Ansi4Bit) -> u8 {
+    swap_red_and_blue(foreground.compact()) | swap_red_and_blue(background.compact()) << 4
+}
+
+fn swap_red_and_blue(color: u8) -> u8 {
+    color & 0b1010 | (color & 0b1) << 2 | (color & 0b100) >> 2
+}
+
+impl<C: Color> AsciiImage<C> {
+    /// Encodes the image as an uncompressed XBin file, followed by a SAUCE record.
+    ///
+    /// # Errors
+    /// If the width or height exceed `u16::MAX`.
+    pub fn to_xbin(&self, sauce: &Sauce) -> Result<Vec<u8>, AnsiArtError> {
+        let width: u16 = self.width().try_into().map_err(|_| AnsiArtError::TooLarge)?;
+        let height: u16 = self.height().try_into().map_err(|_| AnsiArtError::TooLarge)?;
+
+        let mut bytes = Vec::with_capacity(11 + 2 * self.cells().len());
+
+        bytes.extend(ID);
+        bytes.extend(width.to_le_bytes());
+        bytes.extend(height.to_le_bytes());
+        bytes.push(FONT_HEIGHT);
+        bytes.push(if sauce.ice_colors { NON_BLINK } else { 0 });
+
+        for cell in self.cells() {
+            let cell = DosCell::new(cell, sauce.ice_colors, cp437::encode);
+            bytes.extend([cell.character, attribute(cell.foreground, cell.background)]);
+        }
+
+        // XBin files have their own dimensions, so the record leaves them out.
+        sauce.write(&mut bytes, XBIN, 0, 0, 0);
+
+        Ok(bytes)
+    }
+}
+
+impl AsciiImage<Ansi4Bit> {
+    /// Decodes an XBin file, compressed or not.
+    /// Custom palettes and fonts are ignored.
+    ///
+    /// # Errors
+    /// If the file doesn't start with the XBin header or ends before all cells are read.
+    pub fn from_xbin(file: &[u8]) -> Result<Self, AnsiArtError> {
+        let bytes = &mut &file[..];
+
+        if bytes.split_off(..ID.len()) != Some(ID) {
+            return Err(AnsiArtError::NotXBin);
+        }
+
+        let header = bytes.split_off(..6).ok_or(AnsiArtError::Truncated)?;
+        let width = u16::from_le_bytes([header[0], header[1]]) as u32;
+        let height = u16::from_le_bytes([header[2], header[3]]) as u32;
+        let font_height = header[4] as usize;
+        let flags = header[5];
+
+        let mut skipped = 0;
+        if flags & PALETTE != 0 {
+            skipped += PALETTE_LENGTH;
+        }
+        if flags & FONT != 0 {
+            let characters = if flags & FIVE_HUNDRED_TWELVE_CHARACTERS != 0 { 512 } else { 256 };
+            skipped += font_height * characters;
+        }
+        bytes.split_off(..skipped).ok_or(AnsiArtError::Truncated)?;
+
+        let area = width as usize * height as usize;
+        let ice_colors = flags & NON_BLINK != 0;
+
+        // Every cell takes at least a byte, unless the header lies about the dimensions.
+        let mut cells = Vec::with_capacity(area.min(bytes.len()));
+        let mut push = |character: u8, attribute: u8| {
+            let color = |bits: u8| Ansi4Bit::expand(swap_red_and_blue(bits & 0b1111));
+            let mut background = color(attribute >> 4);
+            background.is_bright &= ice_colors;
+
+            let cell = DosCell {
+                character,
+                foreground: color(attribute),
+                background,
+            };
+            cells.push(cell.to_cell());
+        };
+
+        let mut take = |count: usize| bytes.split_off(..count).ok_or(AnsiArtError::Truncated);
+        let mut remaining = area;
+
+        if flags & COMPRESSED == 0 {
+            // On 32 bit targets the largest images take more bytes than a `usize` can count.
+            let length = area.checked_mul(2).ok_or(AnsiArtError::Truncated)?;
+            for pair in take(length)?.chunks_exact(2) {
+                push(pair[0], pair[1]);
+            }
+        } else {
+            while remaining != 0 {
+                let run = take(1)?[0];
+                // The run length is stored minus one. Runs past the end of the image are cut off.
+                let count = (run as usize & 0b11_1111) + 1;
+                let count = count.min(remaining);
+                remaining -= count;
+
+                match run >> 6 {
+                    // Uncompressed
+                    0b00 => {
+                        for pair in take(2 * count)?.chunks_exact(2) {
+                            push(pair[0], pair[1]);
+                        }
+                    }
+                    // A repeated character with varying attributes
+                    0b01 => {
+                        let character = take(1)?[0];
+                        for &attribute in take(count)? {
+                            push(character, attribute);
+                        }
+                    }
+                    // A repeated attribute with varying characters
+                    0b10 => {
+                        let attribute = take(1)?[0];
+                        for &character in take(count)? {
+                            push(character, attribute);
+                        }
+                    }
+                    // A repeated cell
+                    _ => {
+                        let pair = take(2)?;
+                        for _ in 0..count {
+                            push(pair[0], pair[1]);
+                        }
+                    }
+                }
+            }
+        }
+
+        // The number of cells is exactly the area.
+        Ok(AsciiImage::from_cells(cells, width, height).unwrap_or_default())
+    }
+}
@@ -1,19 +1,127 @@
 use crate::color::Color;
 use crate::sgr::SelectGraphicRendition;
 use std::fmt::{Display, Formatter};
+use std::io::Write;
+
+const BOLD: u8 = 1;
+const DIM: u8 = 2;
+const ITALIC: u8 = 3;
+const UNDERLINE: u8 = 4;
+const REVERSE: u8 = 7;
+const NORMAL_INTENSITY: u8 = 22;
+const NOT_ITALIC: u8 = 23;
+const NOT_UNDERLINED: u8 = 24;
+const NOT_REVERSED: u8 = 27;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AsciiCell<Color> {
     pub background: Color,
     pub foreground: Option<Foreground<Color>>,
+    pub attributes: Attributes,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Foreground<Color> {
     pub color: Color,
     pub character: char,
 }
 
+/// The ansi `SGR` text attributes of a cell.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Attributes {
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// Swaps the foreground and background colors.
+    pub reverse: bool,
+}
+
+impl Attributes {
+    /// Attributes with nothing set.
+    pub const NONE: Attributes = Attributes {
+        bold: false,
+        dim: false,
+        italic: false,
+        underline: false,
+        reverse: false,
+    };
+
+    /// Only bold set.
+    pub const BOLD: Attributes = Attributes {
+        bold: true,
+        ..Attributes::NONE
+    };
+
+    /// Only dim set.
+    pub const DIM: Attributes = Attributes {
+        dim: true,
+        ..Attributes::NONE
+    };
+
+    /// The attributes as bit flags: 1 = bold, 2 = dim, 4 = italic, 8 = underline and 16 = reverse.
+    #[must_use]
+    pub const fn bits(&self) -> u8 {
+        self.bold as u8
+            | (self.dim as u8) << 1
+            | (self.italic as u8) << 2
+            | (self.underline as u8) << 3
+            | (self.reverse as u8) << 4
+    }
+
+    /// The inverse of `bits`. Unknown bits are ignored.
+    #[must_use]
+    pub const fn from_bits(bits: u8) -> Attributes {
+        Attributes {
+            bold: bits & 1 != 0,
+            dim: bits & 1 << 1 != 0,
+            italic: bits & 1 << 2 != 0,
+            underline: bits & 1 << 3 != 0,
+            reverse: bits & 1 << 4 != 0,
+        }
+    }
+
+    /// Writes the ansi `SGR` parameters that change the attributes from `previous` to `self`.
+    /// # Errors
+    /// If writing fails.
+    pub(crate) fn write_difference(&self, previous: Attributes, mut to: impl Write) -> std::io::Result<()> {
+        // Bold and dim can only be turned off together.
+        if (previous.bold && !self.bold) || (previous.dim && !self.dim) {
+            to.write_all(&[NORMAL_INTENSITY])?;
+
+            if self.bold {
+                to.write_all(&[BOLD])?;
+            }
+            if self.dim {
+                to.write_all(&[DIM])?;
+            }
+        } else {
+            if self.bold && !previous.bold {
+                to.write_all(&[BOLD])?;
+            }
+            if self.dim && !previous.dim {
+                to.write_all(&[DIM])?;
+            }
+        }
+
+        if self.italic != previous.italic {
+            to.write_all(&[if self.italic { ITALIC } else { NOT_ITALIC }])?;
+        }
+        if self.underline != previous.underline {
+            to.write_all(&[if self.underline { UNDERLINE } else { NOT_UNDERLINED }])?;
+        }
+        if self.reverse != previous.reverse {
+            to.write_all(&[if self.reverse { REVERSE } else { NOT_REVERSED }])?;
+        }
+
+        Ok(())
+    }
+}
+
 impl<C: Color + PartialEq> AsciiCell<C> {
     pub(crate) fn fmt_with_previous(
         &self,
@@ -22,14 +130,16 @@ impl<C: Color + PartialEq> AsciiCell<C> {
     ) -> std::fmt::Result {
         let mut sgr = SelectGraphicRendition::new(f);
 
-        let character;
         let write_background;
         let write_foreground;
 
         match (previous, self.foreground.as_ref()) {
             (Some(previous), Some(foreground)) => {
                 write_background = previous.background != self.background;
-                write_foreground = (previous.foreground != self.foreground).then_some(foreground);
+                write_foreground = previous
+                    .foreground
+                    .is_none_or(|previous| previous.color != foreground.color)
+                    .then_some(foreground);
             }
             (Some(previous), None) => {
                 write_background = previous.background != self.background;
@@ -45,6 +155,11 @@ impl<C: Color + PartialEq> AsciiCell<C> {
             }
         }
 
+        let previous_attributes = previous.map_or(Attributes::NONE, |previous| previous.attributes);
+        self.attributes
+            .write_difference(previous_attributes, &mut sgr)
+            .map_err(|_| std::fmt::Error)?;
+
         if write_background {
             self.background
                 .write_background(&mut sgr)
@@ -55,15 +170,11 @@ impl<C: Color + PartialEq> AsciiCell<C> {
                 .color
                 .write_foreground(&mut sgr)
                 .map_err(|_| std::fmt::Error)?;
-
-            character = foreground.character;
-        } else {
-            character = ' ';
         }
 
         drop(sgr);
 
-        f.push(character);
+        f.push(self.foreground.map_or(' ', |foreground| foreground.character));
 
         Ok(())
     }
@@ -1,9 +1,10 @@
-use crate::cell::AsciiCell;
+use crate::cell::{AsciiCell, Attributes};
 use crate::color::Color;
+use crate::conversion::Conversion;
 use crate::font::Font;
-use image::{DynamicImage, Pixel, Rgb, SubImage};
+use crate::renderer::{CellRenderer, GradientMatcher};
+use image::Rgb;
 use std::io::Write;
-use crate::color::util::average_color;
 
 const BACKGROUND: u8 = 48;
 const FOREGROUND: u8 = 38;
@@ -50,13 +51,20 @@ impl Color for Ansi24Bit {
     fn write_foreground(&self, mut to: impl Write) -> std::io::Result<()> {
         to.write_all(&[FOREGROUND, SECOND_ARGUMENT, self.r, self.g, self.b])
     }
+}
 
+impl CellRenderer<Ansi24Bit> for GradientMatcher {
     // TODO: Base the character on the alpha.
-    fn new_cell<G: AsRef<[char]>>(view: SubImage<&DynamicImage>, _font: &Font<G>) -> AsciiCell<Self> {
-        let color = average_color(*view).to_rgb();
+    fn render_color<G: AsRef<[char]>>(
+        &self,
+        color: Rgb<u8>,
+        _font: &Font<G>,
+        _conversion: &Conversion,
+    ) -> AsciiCell<Ansi24Bit> {
         AsciiCell {
             background: color.into(),
             foreground: None,
+            attributes: Attributes::NONE,
         }
     }
 }
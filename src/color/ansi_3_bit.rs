@@ -1,10 +1,11 @@
 use crate::cell::AsciiCell;
 use crate::color::variants::ANSI_3_BIT;
 use crate::color::{default_from_rgb, default_new_cell, Color};
+use crate::conversion::Conversion;
 use crate::font::Font;
-use image::{DynamicImage, Pixel, Rgb, SubImage};
+use crate::renderer::{CellRenderer, GradientMatcher};
+use image::Rgb;
 use std::io::Write;
-use crate::color::util::average_color;
 
 pub(super) const BACKGROUND: u8 = 40;
 pub(super) const FOREGROUND: u8 = 30;
@@ -49,8 +50,15 @@ impl Color for Ansi3Bit {
     fn write_foreground(&self, mut to: impl Write) -> std::io::Result<()> {
         to.write_all(&[FOREGROUND + *self as u8])
     }
+}
 
-    fn new_cell<G: AsRef<[char]>>(view: SubImage<&DynamicImage>, font: &Font<G>) -> AsciiCell<Self> {
-        default_new_cell(&ANSI_3_BIT, average_color(*view).to_rgb(), font)
+impl CellRenderer<Ansi3Bit> for GradientMatcher {
+    fn render_color<G: AsRef<[char]>>(
+        &self,
+        color: Rgb<u8>,
+        font: &Font<G>,
+        conversion: &Conversion,
+    ) -> AsciiCell<Ansi3Bit> {
+        default_new_cell(&ANSI_3_BIT, color, font, conversion)
     }
 }
@@ -1,11 +1,12 @@
-use crate::cell::AsciiCell;
+use crate::cell::{AsciiCell, Attributes};
 use crate::color::ansi_3_bit::{Ansi3Bit, BACKGROUND, FOREGROUND};
 use crate::color::variants::ANSI_4_BIT;
 use crate::color::{default_from_rgb, default_new_cell, Color};
+use crate::conversion::Conversion;
 use crate::font::Font;
-use image::{DynamicImage, Pixel, Rgb, SubImage};
+use crate::renderer::{CellRenderer, GradientMatcher};
+use image::{Pixel, Rgb};
 use std::io::Write;
-use crate::color::util::average_color;
 
 const BRIGHT_OFFSET: u8 = 60;
 
@@ -61,6 +62,21 @@ impl Color for Ansi4Bit {
         default_from_rgb(&ANSI_4_BIT, color)
     }
 
+    fn to_rgb_with_attributes(&self, attributes: Attributes) -> Rgb<u8> {
+        // Many terminals draw bold characters using the bright variant of the color.
+        let color = Ansi4Bit {
+            is_bright: self.is_bright || attributes.bold,
+            color: self.color,
+        }
+        .to_rgb();
+
+        if attributes.dim {
+            color.map(|chanel| chanel / 2)
+        } else {
+            color
+        }
+    }
+
     fn write_background(&self, mut to: impl Write) -> std::io::Result<()> {
         to.write_all(&[self.color as u8 + BACKGROUND + u8::from(self.is_bright) * BRIGHT_OFFSET])
     }
@@ -68,8 +84,15 @@ impl Color for Ansi4Bit {
     fn write_foreground(&self, mut to: impl Write) -> std::io::Result<()> {
         to.write_all(&[self.color as u8 + FOREGROUND + u8::from(self.is_bright) * BRIGHT_OFFSET])
     }
+}
 
-    fn new_cell<G: AsRef<[char]>>(view: SubImage<&DynamicImage>, font: &Font<G>) -> AsciiCell<Self> {
-        default_new_cell(&ANSI_4_BIT, average_color(*view).to_rgb(), font)
+impl CellRenderer<Ansi4Bit> for GradientMatcher {
+    fn render_color<G: AsRef<[char]>>(
+        &self,
+        color: Rgb<u8>,
+        font: &Font<G>,
+        conversion: &Conversion,
+    ) -> AsciiCell<Ansi4Bit> {
+        default_new_cell(&ANSI_4_BIT, color, font, conversion)
     }
 }
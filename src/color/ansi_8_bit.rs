@@ -1,13 +1,17 @@
-use crate::cell::AsciiCell;
+use crate::cell::{AsciiCell, Attributes};
+use crate::color::ansi_3_bit::Ansi3Bit;
 use crate::color::ansi_4_bit::Ansi4Bit;
 use crate::color::variants::ANSI_8_BIT;
 use crate::color::{default_from_rgb, default_new_cell, Color};
+use crate::conversion::Conversion;
 use crate::font::Font;
+use crate::renderer::{CellRenderer, GradientMatcher};
 use cube::Cube;
 use grayscale::Grayscale;
-use image::{DynamicImage, Luma, Pixel, Rgb, SubImage};
+use image::{Luma, Pixel, Rgb};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 use std::io::Write;
-use crate::color::util::average_color;
 
 pub mod cube;
 pub mod cube_coordinate;
@@ -34,10 +38,79 @@ impl Default for Ansi8Bit {
     }
 }
 
+impl Ansi8Bit {
+    /// Gets the color with the provided index among the 256 colors.
+    #[must_use]
+    pub const fn from_index(index: u8) -> Ansi8Bit {
+        if index < cube::OFFSET {
+            let color = match index % 8 {
+                0 => Ansi3Bit::Black,
+                1 => Ansi3Bit::Red,
+                2 => Ansi3Bit::Green,
+                3 => Ansi3Bit::Yellow,
+                4 => Ansi3Bit::Blue,
+                5 => Ansi3Bit::Magenta,
+                6 => Ansi3Bit::Cyan,
+                _ => Ansi3Bit::White,
+            };
+
+            Ansi8Bit::Ansi4Bit(Ansi4Bit {
+                is_bright: 8 <= index,
+                color,
+            })
+        } else if index < grayscale::OFFSET {
+            let index = index - cube::OFFSET;
+            Ansi8Bit::Cube(Cube::new(index / 36, index / 6 % 6, index % 6))
+        } else {
+            Ansi8Bit::Grayscale(Grayscale::new(index - grayscale::OFFSET))
+        }
+    }
+
+    /// The index of the color among the 256 colors,
+    /// which is the parameter used in its escape codes.
+    #[must_use]
+    pub const fn index(&self) -> u8 {
+        match self {
+            Ansi8Bit::Ansi4Bit(color) => color.color as u8 + 8 * color.is_bright as u8,
+            Ansi8Bit::Cube(color) => cube::OFFSET + 36 * color.r.get() + 6 * color.g.get() + color.b.get(),
+            Ansi8Bit::Grayscale(color) => grayscale::OFFSET + color.brightness(),
+        }
+    }
+}
+
 impl PartialEq for Ansi8Bit {
     fn eq(&self, other: &Self) -> bool {
-        // TODO: Avoid conversion here.
-        self.to_rgb() == other.to_rgb()
+        self.index() == other.index()
+    }
+}
+
+impl Hash for Ansi8Bit {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index().hash(state);
+    }
+}
+
+impl PartialOrd for Ansi8Bit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ansi8Bit {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.index().cmp(&other.index())
+    }
+}
+
+impl From<u8> for Ansi8Bit {
+    fn from(index: u8) -> Self {
+        Ansi8Bit::from_index(index)
+    }
+}
+
+impl From<Ansi8Bit> for u8 {
+    fn from(color: Ansi8Bit) -> Self {
+        color.index()
     }
 }
 
@@ -63,6 +136,14 @@ impl Color for Ansi8Bit {
         default_from_rgb(&ANSI_8_BIT, color)
     }
 
+    fn to_rgb_with_attributes(&self, attributes: Attributes) -> Rgb<u8> {
+        match self {
+            Ansi8Bit::Ansi4Bit(color) => color.to_rgb_with_attributes(attributes),
+            Ansi8Bit::Cube(color) => color.to_rgb_with_attributes(attributes),
+            Ansi8Bit::Grayscale(color) => color.to_rgb_with_attributes(attributes),
+        }
+    }
+
     fn write_background(&self, to: impl Write) -> std::io::Result<()> {
         match self {
             Ansi8Bit::Ansi4Bit(color) => color.write_background(to),
@@ -78,8 +159,15 @@ impl Color for Ansi8Bit {
             Ansi8Bit::Grayscale(color) => color.write_foreground(to),
         }
     }
+}
 
-    fn new_cell<G: AsRef<[char]>>(view: SubImage<&DynamicImage>, font: &Font<G>) -> AsciiCell<Self> {
-        default_new_cell(&ANSI_8_BIT, average_color(*view).to_rgb(), font)
+impl CellRenderer<Ansi8Bit> for GradientMatcher {
+    fn render_color<G: AsRef<[char]>>(
+        &self,
+        color: Rgb<u8>,
+        font: &Font<G>,
+        conversion: &Conversion,
+    ) -> AsciiCell<Ansi8Bit> {
+        default_new_cell(&ANSI_8_BIT, color, font, conversion)
     }
 }
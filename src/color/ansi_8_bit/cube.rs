@@ -3,13 +3,15 @@ use crate::color::ansi_8_bit::cube_coordinate::CubeCoordinate;
 use crate::color::ansi_8_bit::{BACKGROUND, FOREGROUND, SECOND_ARGUMENT};
 use crate::color::variants::CUBE;
 use crate::color::{default_new_cell, Color};
+use crate::conversion::Conversion;
 use crate::font::Font;
-use image::{DynamicImage, Pixel, Rgb, SubImage};
+use crate::renderer::{CellRenderer, GradientMatcher};
+use image::Rgb;
 use std::io::Write;
 use rounded_div::RoundedDiv;
-use crate::color::util::average_color;
 
-const OFFSET: u8 = 16;
+/// The index of the first cube color among the 256 colors.
+pub(super) const OFFSET: u8 = 16;
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
 pub struct Cube {
@@ -60,8 +62,15 @@ impl Color for Cube {
             OFFSET + 36 * self.r.get() + 6 * self.g.get() + self.b.get(),
         ])
     }
+}
 
-    fn new_cell<G: AsRef<[char]>>(view: SubImage<&DynamicImage>, font: &Font<G>) -> AsciiCell<Self> {
-        default_new_cell(&CUBE, average_color(*view).to_rgb(), font)
+impl CellRenderer<Cube> for GradientMatcher {
+    fn render_color<G: AsRef<[char]>>(
+        &self,
+        color: Rgb<u8>,
+        font: &Font<G>,
+        conversion: &Conversion,
+    ) -> AsciiCell<Cube> {
+        default_new_cell(&CUBE, color, font, conversion)
     }
 }
@@ -2,13 +2,15 @@ use crate::cell::AsciiCell;
 use crate::color::ansi_8_bit::{BACKGROUND, FOREGROUND, SECOND_ARGUMENT};
 use crate::color::variants::GRAYSCALE;
 use crate::color::{default_new_cell, Color};
+use crate::conversion::Conversion;
 use crate::font::Font;
-use image::{DynamicImage, Luma, Pixel, Rgb, SubImage};
+use crate::renderer::{CellRenderer, GradientMatcher};
+use image::{Luma, Pixel, Rgb};
 use num_rational::Ratio;
 use std::io::Write;
-use crate::color::util::average_color;
 
-const OFFSET: u8 = 232;
+/// The index of the first grayscale color among the 256 colors.
+pub(super) const OFFSET: u8 = 232;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
 pub struct Grayscale {
@@ -73,8 +75,15 @@ impl Color for Grayscale {
     fn write_foreground(&self, mut to: impl Write) -> std::io::Result<()> {
         to.write_all(&[FOREGROUND, SECOND_ARGUMENT, OFFSET + self.brightness])
     }
+}
 
-    fn new_cell<G: AsRef<[char]>>(view: SubImage<&DynamicImage>, font: &Font<G>) -> AsciiCell<Self> {
-        default_new_cell(&GRAYSCALE, average_color(*view).to_rgb(), font)
+impl CellRenderer<Grayscale> for GradientMatcher {
+    fn render_color<G: AsRef<[char]>>(
+        &self,
+        color: Rgb<u8>,
+        font: &Font<G>,
+        conversion: &Conversion,
+    ) -> AsciiCell<Grayscale> {
+        default_new_cell(&GRAYSCALE, color, font, conversion)
     }
 }
@@ -1,12 +1,14 @@
-use crate::cell::{AsciiCell, Foreground};
+use crate::cell::{AsciiCell, Attributes, Foreground};
 use crate::color::Color;
+use crate::conversion::Conversion;
 use crate::font::Font;
-use image::{DynamicImage, Luma, Pixel, Rgb, SubImage};
+use crate::renderer::{CellRenderer, GradientMatcher};
+use image::{Luma, Pixel, Rgb};
 use num_rational::Ratio;
 use std::io::Write;
-use crate::color::util::average_color;
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Colorless;
 
 impl Color for Colorless {
@@ -25,19 +27,33 @@ impl Color for Colorless {
     fn write_foreground(&self, _: impl Write) -> std::io::Result<()> {
         Ok(())
     }
+}
+
+/// The character of the gradient that stands for the luminance of `color`,
+/// from the first one for black to the last one for white.
+pub(crate) fn shade<G: AsRef<[char]>>(color: Rgb<u8>, font: &Font<G>) -> char {
+    let Luma([luma]) = color.to_luma();
+    let luma = Ratio::new(luma as usize, u8::MAX as usize);
 
-    fn new_cell<G: AsRef<[char]>>(view: SubImage<&DynamicImage>, font: &Font<G>) -> AsciiCell<Self> {
-        let Luma([luma]) = average_color(*view).to_luma();
-        let luma = Ratio::new(luma as usize, u8::MAX as usize);
+    let index = (luma * (font.gradient().len() - 1)).round().to_integer();
 
-        let index = (luma * (font.gradient().len() - 1)).round().to_integer();
+    font.gradient()[index]
+}
 
+impl CellRenderer<Colorless> for GradientMatcher {
+    fn render_color<G: AsRef<[char]>>(
+        &self,
+        color: Rgb<u8>,
+        font: &Font<G>,
+        _conversion: &Conversion,
+    ) -> AsciiCell<Colorless> {
         AsciiCell {
             background: Colorless,
             foreground: Some(Foreground {
                 color: Colorless,
-                character: font.gradient()[index],
+                character: shade(color, font),
             }),
+            attributes: Attributes::NONE,
         }
     }
 }
@@ -0,0 +1,128 @@
+use crate::color::ansi_24_bit::Ansi24Bit;
+use crate::color::ansi_3_bit::Ansi3Bit;
+use crate::color::ansi_4_bit::Ansi4Bit;
+use crate::color::ansi_8_bit::cube::Cube;
+use crate::color::ansi_8_bit::grayscale::Grayscale;
+use crate::color::ansi_8_bit::Ansi8Bit;
+use crate::color::colorless::Colorless;
+use crate::color::mirc::{self, Mirc};
+use crate::color::variants::ANSI_3_BIT;
+use crate::color::Color;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// A color which can be stored in fewer bytes than its own representation,
+/// e.g. an 8-bit color as a single byte.
+pub trait CompactColor: Color {
+    /// The compact representation.
+    type Compact: Copy + Eq + Hash + Debug;
+
+    #[must_use]
+    fn compact(&self) -> Self::Compact;
+
+    /// The inverse of `compact`.
+    #[must_use]
+    fn expand(compact: Self::Compact) -> Self;
+}
+
+impl CompactColor for Colorless {
+    type Compact = ();
+
+    fn compact(&self) -> Self::Compact {}
+
+    fn expand((): Self::Compact) -> Self {
+        Colorless
+    }
+}
+
+impl CompactColor for Ansi3Bit {
+    type Compact = u8;
+
+    fn compact(&self) -> u8 {
+        *self as u8
+    }
+
+    fn expand(compact: u8) -> Self {
+        ANSI_3_BIT[compact as usize % ANSI_3_BIT.len()]
+    }
+}
+
+/// Stored as the color in the lower three bits and the brightness in the fourth.
+impl CompactColor for Ansi4Bit {
+    type Compact = u8;
+
+    fn compact(&self) -> u8 {
+        self.color.compact() | u8::from(self.is_bright) << 3
+    }
+
+    fn expand(compact: u8) -> Self {
+        Ansi4Bit {
+            is_bright: compact & 0b1000 != 0,
+            color: Ansi3Bit::expand(compact & 0b111),
+        }
+    }
+}
+
+/// Stored as the index of the color among the 256 colors.
+impl CompactColor for Ansi8Bit {
+    type Compact = u8;
+
+    fn compact(&self) -> u8 {
+        self.index()
+    }
+
+    fn expand(compact: u8) -> Self {
+        Ansi8Bit::from_index(compact)
+    }
+}
+
+/// Stored as the index of the color within the cube.
+impl CompactColor for Cube {
+    type Compact = u8;
+
+    fn compact(&self) -> u8 {
+        36 * self.r.get() + 6 * self.g.get() + self.b.get()
+    }
+
+    fn expand(compact: u8) -> Self {
+        Cube::new(compact / 36, compact / 6 % 6, compact % 6)
+    }
+}
+
+impl CompactColor for Grayscale {
+    type Compact = u8;
+
+    fn compact(&self) -> u8 {
+        self.brightness()
+    }
+
+    fn expand(compact: u8) -> Self {
+        Grayscale::new(compact)
+    }
+}
+
+impl CompactColor for Ansi24Bit {
+    type Compact = [u8; 3];
+
+    fn compact(&self) -> [u8; 3] {
+        self.to_rgb().0
+    }
+
+    fn expand(compact: [u8; 3]) -> Self {
+        let [r, g, b] = compact;
+        Ansi24Bit::new(r, g, b)
+    }
+}
+
+/// Stored as the color code.
+impl CompactColor for Mirc {
+    type Compact = u8;
+
+    fn compact(&self) -> u8 {
+        self.code()
+    }
+
+    fn expand(compact: u8) -> Self {
+        Mirc::from_code(compact % mirc::COUNT).unwrap_or_default()
+    }
+}
@@ -0,0 +1,134 @@
+//! The colors of mIRC and other IRC clients, see [`crate::irc`].
+
+use crate::cell::AsciiCell;
+use crate::color::ansi_24_bit::Ansi24Bit;
+use crate::color::{default_from_rgb, default_new_cell, Color};
+use crate::conversion::Conversion;
+use crate::font::Font;
+use crate::renderer::{CellRenderer, GradientMatcher};
+use image::Rgb;
+use std::io::Write;
+
+/// The number of colors, the codes 0 to 98. Code 99 means the client's default color.
+pub const COUNT: u8 = 99;
+
+/// The number of classic colors, which all clients support.
+/// The others are known as the extended colors.
+pub const CLASSIC_COUNT: u8 = 16;
+
+/// The colors as recommended by mIRC.
+const RGB: [[u8; 3]; COUNT as usize] = [
+    // The classic colors: white, black, blue, green, red, brown, purple, orange,
+    // yellow, light green, cyan, light cyan, light blue, pink, gray and light gray.
+    [0xff, 0xff, 0xff], [0x00, 0x00, 0x00], [0x00, 0x00, 0x7f], [0x00, 0x93, 0x00],
+    [0xff, 0x00, 0x00], [0x7f, 0x00, 0x00], [0x9c, 0x00, 0x9c], [0xfc, 0x7f, 0x00],
+    [0xff, 0xff, 0x00], [0x00, 0xfc, 0x00], [0x00, 0x93, 0x93], [0x00, 0xff, 0xff],
+    [0x00, 0x00, 0xfc], [0xff, 0x00, 0xff], [0x7f, 0x7f, 0x7f], [0xd2, 0xd2, 0xd2],
+    // The extended colors: six rows of twelve hues, from dark to light, then eleven grays.
+    [0x47, 0x00, 0x00], [0x47, 0x21, 0x00], [0x47, 0x47, 0x00], [0x32, 0x47, 0x00],
+    [0x00, 0x47, 0x00], [0x00, 0x47, 0x2c], [0x00, 0x47, 0x47], [0x00, 0x27, 0x47],
+    [0x00, 0x00, 0x47], [0x2e, 0x00, 0x47], [0x47, 0x00, 0x47], [0x47, 0x00, 0x2a],
+    [0x74, 0x00, 0x00], [0x74, 0x3a, 0x00], [0x74, 0x74, 0x00], [0x51, 0x74, 0x00],
+    [0x00, 0x74, 0x00], [0x00, 0x74, 0x49], [0x00, 0x74, 0x74], [0x00, 0x40, 0x74],
+    [0x00, 0x00, 0x74], [0x4b, 0x00, 0x74], [0x74, 0x00, 0x74], [0x74, 0x00, 0x45],
+    [0xb5, 0x00, 0x00], [0xb5, 0x63, 0x00], [0xb5, 0xb5, 0x00], [0x7d, 0xb5, 0x00],
+    [0x00, 0xb5, 0x00], [0x00, 0xb5, 0x71], [0x00, 0xb5, 0xb5], [0x00, 0x63, 0xb5],
+    [0x00, 0x00, 0xb5], [0x75, 0x00, 0xb5], [0xb5, 0x00, 0xb5], [0xb5, 0x00, 0x6b],
+    [0xff, 0x00, 0x00], [0xff, 0x8c, 0x00], [0xff, 0xff, 0x00], [0xb2, 0xff, 0x00],
+    [0x00, 0xff, 0x00], [0x00, 0xff, 0xa0], [0x00, 0xff, 0xff], [0x00, 0x8c, 0xff],
+    [0x00, 0x00, 0xff], [0xa5, 0x00, 0xff], [0xff, 0x00, 0xff], [0xff, 0x00, 0x98],
+    [0xff, 0x59, 0x59], [0xff, 0xb4, 0x59], [0xff, 0xff, 0x71], [0xcf, 0xff, 0x60],
+    [0x6f, 0xff, 0x6f], [0x65, 0xff, 0xc9], [0x6d, 0xff, 0xff], [0x59, 0xb4, 0xff],
+    [0x59, 0x59, 0xff], [0xc4, 0x59, 0xff], [0xff, 0x66, 0xff], [0xff, 0x59, 0xbc],
+    [0xff, 0x9c, 0x9c], [0xff, 0xd3, 0x9c], [0xff, 0xff, 0x9c], [0xe2, 0xff, 0x9c],
+    [0x9c, 0xff, 0x9c], [0x9c, 0xff, 0xdb], [0x9c, 0xff, 0xff], [0x9c, 0xd3, 0xff],
+    [0x9c, 0x9c, 0xff], [0xdc, 0x9c, 0xff], [0xff, 0x9c, 0xff], [0xff, 0x94, 0xd3],
+    [0x00, 0x00, 0x00], [0x13, 0x13, 0x13], [0x28, 0x28, 0x28], [0x36, 0x36, 0x36],
+    [0x4d, 0x4d, 0x4d], [0x65, 0x65, 0x65], [0x81, 0x81, 0x81], [0x9f, 0x9f, 0x9f],
+    [0xbc, 0xbc, 0xbc], [0xe2, 0xe2, 0xe2], [0xff, 0xff, 0xff],
+];
+
+const ALL: [Mirc; COUNT as usize] = {
+    let mut colors = [Mirc { code: 0 }; COUNT as usize];
+
+    let mut code = 0;
+    while code < COUNT {
+        colors[code as usize] = Mirc { code };
+        code += 1;
+    }
+
+    colors
+};
+
+/// A color of the mIRC palette, identified by its color code.
+/// Drawn as 24-bit color when output as ansi.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Mirc {
+    code: u8,
+}
+
+impl Default for Mirc {
+    /// Black
+    fn default() -> Self {
+        Mirc { code: 1 }
+    }
+}
+
+impl Mirc {
+    /// Gets the color with the provided code, or `None` if the code is at least [`COUNT`].
+    #[must_use]
+    pub const fn from_code(code: u8) -> Option<Mirc> {
+        if code < COUNT {
+            Some(Mirc { code })
+        } else {
+            None
+        }
+    }
+
+    /// The color code, on the interval [0; 98].
+    #[must_use]
+    pub const fn code(&self) -> u8 {
+        self.code
+    }
+
+    /// Whether the color is one of the 16 classic colors.
+    #[must_use]
+    pub const fn is_classic(&self) -> bool {
+        self.code < CLASSIC_COUNT
+    }
+
+    /// Approximates `color` using only the classic colors.
+    #[must_use]
+    pub fn classic_from_rgb(color: Rgb<u8>) -> Self {
+        default_from_rgb(&ALL[..CLASSIC_COUNT as usize], color)
+    }
+}
+
+impl Color for Mirc {
+    fn to_rgb(&self) -> Rgb<u8> {
+        Rgb(RGB[self.code as usize])
+    }
+
+    fn from_rgb(color: Rgb<u8>) -> Self {
+        default_from_rgb(&ALL, color)
+    }
+
+    fn write_background(&self, to: impl Write) -> std::io::Result<()> {
+        Ansi24Bit::from(self.to_rgb()).write_background(to)
+    }
+
+    fn write_foreground(&self, to: impl Write) -> std::io::Result<()> {
+        Ansi24Bit::from(self.to_rgb()).write_foreground(to)
+    }
+}
+
+impl CellRenderer<Mirc> for GradientMatcher {
+    fn render_color<G: AsRef<[char]>>(
+        &self,
+        color: Rgb<u8>,
+        font: &Font<G>,
+        conversion: &Conversion,
+    ) -> AsciiCell<Mirc> {
+        default_new_cell(&ALL, color, font, conversion)
+    }
+}
@@ -3,17 +3,19 @@ pub mod ansi_3_bit;
 pub mod ansi_4_bit;
 pub mod ansi_8_bit;
 pub mod colorless;
-mod util;
-mod variants;
+pub mod compact;
+pub mod mirc;
+pub(crate) mod util;
+pub mod variants;
 
-use crate::cell::{AsciiCell, Foreground};
-use image::{DynamicImage, Rgb, SubImage};
+use crate::cell::{AsciiCell, Attributes, Foreground};
+use image::{Pixel, Rgb};
 use std::io::Write;
 
-use crate::color::util::{interpolate, square_distance};
+use crate::color::util::{interpolate, interpolate_linear, square_distance, to_linear};
+use crate::conversion::Conversion;
 use crate::font::Font;
 
-// TODO: Add compact colors.
 pub trait Color: Copy {
     #[must_use]
     fn to_rgb(&self) -> Rgb<u8>;
@@ -21,6 +23,19 @@ pub trait Color: Copy {
     #[must_use]
     fn from_rgb(color: Rgb<u8>) -> Self;
 
+    /// The color of a character drawn in `self` with the provided attributes.
+    /// By default dim characters are drawn at half intensity and bold ones aren't affected.
+    #[must_use]
+    fn to_rgb_with_attributes(&self, attributes: Attributes) -> Rgb<u8> {
+        let color = self.to_rgb();
+
+        if attributes.dim {
+            color.map(|channel| channel / 2)
+        } else {
+            color
+        }
+    }
+
     /// Writes the ansi `SGR` parameters to color the background.
     /// # Errors
     /// If writing fails.
@@ -29,27 +44,40 @@ pub trait Color: Copy {
     /// # Errors
     //  If writing fails.
     fn write_foreground(&self, to: impl Write) -> std::io::Result<()>;
-
-    // TODO: Take alpha channel into consideration.
-    // TODO: Replace `color: Rgb<u8>` with `pixels: SubImage`.
-    /// Creates a new `AsciiCell` with `Self` as the color type.
-    /// `color` represents the color to approximate.
-    /// `max_coverage` is how much coverage the last character in `gradient` provides.
-    #[must_use]
-    fn new_cell<G: AsRef<[char]>>(view: SubImage<&DynamicImage>, font: &Font<G>) -> AsciiCell<Self>
-    where
-        Self: Sized;
 }
 
-pub(super) fn default_new_cell<C: Color + Default, G: AsRef<[char]>>(
+/// The cell closest to `color`, out of every combination of a background and a foreground from `colors`
+/// with a character from the font's gradient, drawn in the renditions enabled by `conversion`.
+///
+/// This is how the [`GradientMatcher`](crate::renderer::GradientMatcher) draws the palettes of this crate.
+/// Colors from other crates may use it to implement `CellRenderer` for the `GradientMatcher`.
+/// The search takes time proportional to the square of the number of colors.
+pub fn default_new_cell<C: Color + Default, G: AsRef<[char]>>(
     colors: &[C],
     color: Rgb<u8>,
     font: &Font<G>,
+    conversion: &Conversion,
 ) -> AsciiCell<C> {
     let mut closest = u32::MAX;
     let mut background = C::default();
     let mut foreground = C::default();
     let mut character = ' ';
+    let mut attributes = Attributes::NONE;
+
+    // The ways a character may be drawn.
+    let mut renditions = vec![Attributes::NONE];
+    if conversion.attribute_shading {
+        renditions.extend([Attributes::BOLD, Attributes::DIM]);
+    }
+    // Reversing a plain character gives the same colors as swapping the background and foreground,
+    // so only the bold and dim renditions are reversed.
+    if conversion.reverse_video {
+        let reversed = renditions.iter().skip(1).map(|rendition| Attributes {
+            reverse: true,
+            ..*rendition
+        });
+        renditions.extend(reversed.collect::<Vec<_>>());
+    }
 
     // Cache coverages
     let mut coverages = Vec::with_capacity(font.gradient().len());
@@ -59,42 +87,86 @@ pub(super) fn default_new_cell<C: Color + Default, G: AsRef<[char]>>(
     // Using a slice improves performance
     let coverages = coverages.as_slice();
 
-    // Go through all possible color-color-character combinations and find the closest
-    for from in colors {
-        let from_rgb = from.to_rgb();
-
-        for to in colors {
-            let to_rgb = to.to_rgb();
-
-            for (index, char) in font.gradient().iter().enumerate() {
-                let interpolation_parameter = coverages[index];
-
-                let interpolation = interpolate(from_rgb, to_rgb, interpolation_parameter);
-
-                let distance = square_distance(color, interpolation);
+    // The colors in sRGB and in linear light, computed once per palette entry and rendition.
+    let backgrounds: Vec<_> = colors
+        .iter()
+        .map(|color| {
+            let rgb = color.to_rgb();
+            (rgb, to_linear(rgb))
+        })
+        .collect();
+    let foregrounds: Vec<Vec<_>> = colors
+        .iter()
+        .map(|color| {
+            let rendered = renditions.iter().map(|rendition| {
+                let rgb = color.to_rgb_with_attributes(*rendition);
+                (rgb, to_linear(rgb))
+            });
+            rendered.collect()
+        })
+        .collect();
 
-                if distance < closest {
-                    closest = distance;
-                    background = *from;
-                    foreground = *to;
-                    character = *char;
+    // Go through all possible color-color-character combinations and find the closest
+    let linear_light = conversion.linear_light;
+
+    for (from, &(from_rgb, from_rgb_linear)) in colors.iter().zip(&backgrounds) {
+        for (to, rendered) in colors.iter().zip(&foregrounds) {
+            for (rendition, &(to_rgb, to_rgb_linear)) in renditions.iter().zip(rendered) {
+                // When reversed, the character is drawn in the background color and vice versa.
+                let (uncovered, covered) = if rendition.reverse {
+                    (to_rgb, from_rgb)
+                } else {
+                    (from_rgb, to_rgb)
+                };
+                let (uncovered_linear, covered_linear) = if rendition.reverse {
+                    (to_rgb_linear, from_rgb_linear)
+                } else {
+                    (from_rgb_linear, to_rgb_linear)
+                };
+
+                for (index, char) in font.gradient().iter().enumerate() {
+                    let interpolation_parameter = coverages[index];
+
+                    let interpolation = if linear_light {
+                        interpolate_linear(uncovered_linear, covered_linear, interpolation_parameter)
+                    } else {
+                        interpolate(uncovered, covered, interpolation_parameter)
+                    };
+
+                    let distance = square_distance(color, interpolation);
+
+                    if distance < closest {
+                        closest = distance;
+                        background = *from;
+                        foreground = *to;
+                        character = *char;
+                        attributes = *rendition;
+                    }
                 }
             }
         }
     }
 
-    let foreground = (character != ' ').then_some(Foreground {
+    // A reversed space is filled with the foreground color, so it needs to be kept.
+    let foreground = (character != ' ' || attributes.reverse).then_some(Foreground {
         color: foreground,
         character,
     });
 
+    // The rendition only affects the character.
+    if foreground.is_none() {
+        attributes = Attributes::NONE;
+    }
+
     AsciiCell {
         background,
         foreground,
+        attributes,
     }
 }
 
-pub(super) fn default_from_rgb<C: Color + Default>(colors: &[C], color: Rgb<u8>) -> C {
+/// The color from `colors` closest to `color`, or the default color if `colors` is empty.
+pub fn default_from_rgb<C: Color + Default>(colors: &[C], color: Rgb<u8>) -> C {
     colors
         .iter()
         .copied()
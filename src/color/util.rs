@@ -1,7 +1,78 @@
-use image::{GenericImageView, Pixel, Rgb, Rgba};
+use image::{GenericImageView, Pixel, Primitive, Rgb, Rgba};
 use num_rational::Ratio;
 use num_traits::ToPrimitive;
 use rounded_div::RoundedDiv;
+use std::sync::OnceLock;
+
+/// Converts an sRGB channel to linear light on the interval [0; `u16::MAX`].
+#[inline]
+pub(crate) fn decode(channel: u8) -> u16 {
+    static TABLE: OnceLock<[u16; 256]> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        std::array::from_fn(|channel| {
+            let channel = channel as f64 / u8::MAX as f64;
+
+            let linear = if channel <= 0.04045 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            };
+
+            (linear * u16::MAX as f64).round() as u16
+        })
+    })[channel as usize]
+}
+
+/// Converts an sRGB color to linear light.
+#[inline]
+pub(super) fn to_linear(Rgb(color): Rgb<u8>) -> Rgb<u16> {
+    Rgb(color.map(decode))
+}
+
+/// Converts a linear light channel on the interval [0; `u16::MAX`] to sRGB.
+#[inline]
+pub(crate) fn encode(linear: u16) -> u8 {
+    static TABLE: OnceLock<Box<[u8]>> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        (0..=u16::MAX)
+            .map(|linear| {
+                let linear = linear as f64 / u16::MAX as f64;
+
+                let channel = if linear <= 0.0031308 {
+                    linear * 12.92
+                } else {
+                    1.055 * linear.powf(1.0 / 2.4) - 0.055
+                };
+
+                (channel * u8::MAX as f64).round() as u8
+            })
+            .collect()
+    })[linear as usize]
+}
+
+/// Scales a channel of any type to the interval [0; 1].
+#[inline]
+pub(crate) fn normalize<S: Primitive>(channel: S) -> f32 {
+    let max = S::DEFAULT_MAX_VALUE.to_f32().unwrap_or(1.0);
+    (channel.to_f32().unwrap_or_default() / max).clamp(0.0, 1.0)
+}
+
+/// Converts a pixel of any type to 8-bit RGBA.
+#[inline]
+pub(crate) fn to_rgba8<P: Pixel>(pixel: P) -> Rgba<u8> {
+    // 8-bit channels, which are by far the most common, are used as is.
+    let is_8_bit = P::Subpixel::DEFAULT_MAX_VALUE.to_u8() == Some(u8::MAX);
+
+    Rgba(pixel.to_rgba().0.map(|channel| {
+        if is_8_bit {
+            channel.to_u8().unwrap_or_default()
+        } else {
+            (normalize(channel) * u8::MAX as f32).round() as u8
+        }
+    }))
+}
 
 #[inline]
 pub(super) fn map2<A: Copy, B: Copy, R>(a: Rgb<A>, b: Rgb<B>, mut f: impl FnMut(A, B) -> R) -> Rgb<R> {
@@ -23,6 +94,27 @@ pub(super) fn interpolate(from: Rgb<u8>, to: Rgb<u8>, t: Ratio<u32>) -> Rgb<u8>
     })
 }
 
+/// Like `interpolate`, but mixes the colors in linear light.
+/// The colors are given in linear light and the result is encoded to sRGB.
+#[inline]
+pub(super) fn interpolate_linear(from: Rgb<u16>, to: Rgb<u16>, t: Ratio<u32>) -> Rgb<u8> {
+    map2(from, to, |from, to| {
+        let (numerator, denominator) = t.into_raw();
+
+        let from = from as u64;
+        let to = to as u64;
+        let numerator = numerator as u64;
+        let denominator = denominator as u64;
+
+        encode(
+            ((denominator - numerator) * from + numerator * to)
+                .rounded_div(denominator)
+                .try_into()
+                .unwrap_or(u16::MAX),
+        )
+    })
+}
+
 #[inline]
 pub(super) fn square_distance(a: Rgb<u8>, b: Rgb<u8>) -> u32 {
     let r = u8::abs_diff(a.0[0], b.0[0]) as u32;
@@ -32,7 +124,10 @@ pub(super) fn square_distance(a: Rgb<u8>, b: Rgb<u8>) -> u32 {
     r * r + g * g + b * b
 }
 
-pub(super) fn average_color(image: impl GenericImageView) -> Rgba<u8> {
+/// Averages the colors of the image.
+/// If `linear_light` is set, the color channels are averaged in linear light,
+/// which is how the colors mix on screen.
+pub(crate) fn average_color(image: &impl GenericImageView, linear_light: bool) -> Rgba<u8> {
     let (width, height) = image.dimensions();
     let area = (width * height) as u64;
 
@@ -43,11 +138,18 @@ pub(super) fn average_color(image: impl GenericImageView) -> Rgba<u8> {
 
     for x in 0..width {
         for y in 0..height {
-            let color = image.get_pixel(x, y).to_rgba();
-            r += color.0[0].to_u64().unwrap_or_default();
-            g += color.0[1].to_u64().unwrap_or_default();
-            b += color.0[2].to_u64().unwrap_or_default();
-            a += color.0[3].to_u64().unwrap_or_default();
+            let Rgba([red, green, blue, alpha]) = to_rgba8(image.get_pixel(x, y));
+
+            if linear_light {
+                r += decode(red) as u64;
+                g += decode(green) as u64;
+                b += decode(blue) as u64;
+            } else {
+                r += red as u64;
+                g += green as u64;
+                b += blue as u64;
+            }
+            a += alpha as u64;
         }
     }
 
@@ -56,5 +158,9 @@ pub(super) fn average_color(image: impl GenericImageView) -> Rgba<u8> {
     b /= area;
     a /= area;
 
+    if linear_light {
+        return Rgba([encode(r as u16), encode(g as u16), encode(b as u16), a as u8]);
+    }
+
     Rgba([r as u8, g as u8, b as u8, a as u8])
 }
\ No newline at end of file
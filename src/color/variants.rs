@@ -1,10 +1,12 @@
+//! Every color of each palette.
+
 use crate::color::ansi_3_bit::Ansi3Bit;
 use crate::color::ansi_4_bit::Ansi4Bit;
 use crate::color::ansi_8_bit::cube::Cube;
 use crate::color::ansi_8_bit::grayscale::Grayscale;
 use crate::color::ansi_8_bit::Ansi8Bit;
 
-pub(super) const ANSI_3_BIT: [Ansi3Bit; 8] = [
+pub const ANSI_3_BIT: [Ansi3Bit; 8] = [
     Ansi3Bit::Black,
     Ansi3Bit::Red,
     Ansi3Bit::Green,
@@ -15,7 +17,7 @@ pub(super) const ANSI_3_BIT: [Ansi3Bit; 8] = [
     Ansi3Bit::White,
 ];
 
-pub(super) const ANSI_4_BIT: [Ansi4Bit; 16] = [
+pub const ANSI_4_BIT: [Ansi4Bit; 16] = [
     Ansi4Bit::new_bright(Ansi3Bit::Black),
     Ansi4Bit::new_bright(Ansi3Bit::Red),
     Ansi4Bit::new_bright(Ansi3Bit::Green),
@@ -34,7 +36,7 @@ pub(super) const ANSI_4_BIT: [Ansi4Bit; 16] = [
     Ansi4Bit::new_non_bright(Ansi3Bit::White),
 ];
 
-pub(super) const CUBE: [Cube; 216] = [
+pub const CUBE: [Cube; 216] = [
     Cube::new(0, 0, 0),
     Cube::new(0, 0, 1),
     Cube::new(0, 0, 2),
@@ -253,7 +255,8 @@ pub(super) const CUBE: [Cube; 216] = [
     Cube::new(5, 5, 5),
 ];
 
-pub(super) const GRAYSCALE: [Grayscale; 24] = [
+pub const GRAYSCALE: [Grayscale; 24] = [
+    Grayscale::new(0),
     Grayscale::new(1),
     Grayscale::new(2),
     Grayscale::new(3),
@@ -277,18 +280,10 @@ pub(super) const GRAYSCALE: [Grayscale; 24] = [
     Grayscale::new(21),
     Grayscale::new(22),
     Grayscale::new(23),
-    Grayscale::new(24),
 ];
 
-pub(super) const ANSI_8_BIT: [Ansi8Bit; 256] = [
-    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Black)),
-    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Red)),
-    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Green)),
-    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Yellow)),
-    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Blue)),
-    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Magenta)),
-    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Cyan)),
-    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::White)),
+/// Ordered by index, so that `ANSI_8_BIT[index as usize].index() == index`.
+pub const ANSI_8_BIT: [Ansi8Bit; 256] = [
     Ansi8Bit::Ansi4Bit(Ansi4Bit::new_non_bright(Ansi3Bit::Black)),
     Ansi8Bit::Ansi4Bit(Ansi4Bit::new_non_bright(Ansi3Bit::Red)),
     Ansi8Bit::Ansi4Bit(Ansi4Bit::new_non_bright(Ansi3Bit::Green)),
@@ -297,6 +292,14 @@ pub(super) const ANSI_8_BIT: [Ansi8Bit; 256] = [
     Ansi8Bit::Ansi4Bit(Ansi4Bit::new_non_bright(Ansi3Bit::Magenta)),
     Ansi8Bit::Ansi4Bit(Ansi4Bit::new_non_bright(Ansi3Bit::Cyan)),
     Ansi8Bit::Ansi4Bit(Ansi4Bit::new_non_bright(Ansi3Bit::White)),
+    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Black)),
+    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Red)),
+    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Green)),
+    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Yellow)),
+    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Blue)),
+    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Magenta)),
+    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::Cyan)),
+    Ansi8Bit::Ansi4Bit(Ansi4Bit::new_bright(Ansi3Bit::White)),
     Ansi8Bit::Cube(Cube::new(0, 0, 0)),
     Ansi8Bit::Cube(Cube::new(0, 0, 1)),
     Ansi8Bit::Cube(Cube::new(0, 0, 2)),
@@ -513,6 +516,7 @@ pub(super) const ANSI_8_BIT: [Ansi8Bit; 256] = [
     Ansi8Bit::Cube(Cube::new(5, 5, 3)),
     Ansi8Bit::Cube(Cube::new(5, 5, 4)),
     Ansi8Bit::Cube(Cube::new(5, 5, 5)),
+    Ansi8Bit::Grayscale(Grayscale::new(0)),
     Ansi8Bit::Grayscale(Grayscale::new(1)),
     Ansi8Bit::Grayscale(Grayscale::new(2)),
     Ansi8Bit::Grayscale(Grayscale::new(3)),
@@ -536,5 +540,4 @@ pub(super) const ANSI_8_BIT: [Ansi8Bit; 256] = [
     Ansi8Bit::Grayscale(Grayscale::new(21)),
     Ansi8Bit::Grayscale(Grayscale::new(22)),
     Ansi8Bit::Grayscale(Grayscale::new(23)),
-    Ansi8Bit::Grayscale(Grayscale::new(24)),
 ];
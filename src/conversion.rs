@@ -0,0 +1,34 @@
+use crate::filter::Filter;
+use crate::line_art::{EdgeDetector, LineCharacters};
+use crate::preprocessing::Preprocessing;
+
+/// Settings for converting an image to ascii which don't depend on the font.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Conversion {
+    /// Adjustments applied to the image before it is converted.
+    pub preprocessing: Preprocessing,
+    /// How the pixels under a cell are combined into its color.
+    pub filter: Filter,
+    /// If set, cells containing strong edges are drawn using directional characters
+    /// (see `line_characters`) instead of the font's gradient.
+    pub line_art: Option<EdgeDetector>,
+    /// The characters used for line art.
+    pub line_characters: LineCharacters,
+    /// Whether bold and dim characters may be used as extra shading levels.
+    /// See [`Color::to_rgb_with_attributes`](crate::color::Color::to_rgb_with_attributes)
+    /// for how they are assumed to look.
+    pub attribute_shading: bool,
+    /// Whether cells may be drawn in reverse video,
+    /// letting the foreground color fill the part of the cell the character doesn't cover.
+    /// This lets bold and dim colors fill most of a cell,
+    /// which gives renders with few colors finer tonal steps.
+    /// It only has an effect together with `attribute_shading`,
+    /// since a plain reversed cell looks like one with its colors swapped.
+    pub reverse_video: bool,
+    /// Whether colors are averaged, and the coverage of a character mixes its colors,
+    /// in linear light rather than in sRGB.
+    /// This models a screen blending the light of the pixels in a cell,
+    /// so fine detail such as dithering averages to a brighter color than in sRGB.
+    /// It is slower than mixing in sRGB.
+    pub linear_light: bool,
+}
@@ -0,0 +1,104 @@
+//! Code page 437, the character set of the IBM PC and of DOS era ANSI art.
+//!
+//! The control characters are mapped to the glyphs the VGA text mode draws for them,
+//! except for `0x00`, which is drawn blank and decodes to a space.
+//! Terminals interpret those bytes as control characters though,
+//! so text meant for a terminal only uses [`encode_printable`].
+//!
+//! Line art drawn with [`LineCharacters::BOX_DRAWING`](crate::line_art::LineCharacters::BOX_DRAWING)
+//! stays within code page 437 as well.
+
+use crate::color::Color;
+use crate::font::Font;
+use crate::image::AsciiImage;
+use num_rational::Ratio;
+
+/// The width divided by the height of the 8 × 16 pixel characters of the VGA text mode.
+const VGA_ASPECT_RATIO: Ratio<u32> = Ratio::new_raw(1, 2);
+
+/// A gradient of the shade characters, covering a quarter of the cell more with each step.
+/// Use it with a maximum coverage of 1, as [`SHADES_FONT`] does.
+pub const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// A gradient of the half blocks, for a maximum coverage of 1, as [`HALF_BLOCKS_FONT`] does.
+///
+/// `▀`, `▌` and `▐` cover as much of the cell as `▄`, so they have no place in a gradient.
+/// Like every character of code page 437 they are still encoded, and flipping an image swaps them.
+pub const HALF_BLOCKS: [char; 3] = [' ', '▄', '█'];
+
+/// [`SHADES`] in the VGA text mode.
+pub const SHADES_FONT: Font<[char; 5]> = Font::new_unchecked(SHADES, Ratio::new_raw(1, 1), VGA_ASPECT_RATIO);
+
+/// [`HALF_BLOCKS`] in the VGA text mode.
+pub const HALF_BLOCKS_FONT: Font<[char; 3]> =
+    Font::new_unchecked(HALF_BLOCKS, Ratio::new_raw(1, 1), VGA_ASPECT_RATIO);
+
+/// The character drawn for every byte.
+const CHARACTERS: [char; 256] = [
+    ' ', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼',
+    '►', '◄', '↕', '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼',
+    ' ', '!', '"', '#', '$', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?',
+    '@', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '[', '\\', ']', '^', '_',
+    '`', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o',
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '{', '|', '}', '~', '⌂',
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+/// The character drawn for `byte`.
+#[must_use]
+pub const fn decode(byte: u8) -> char {
+    CHARACTERS[byte as usize]
+}
+
+/// The byte which draws `character`, or `None` if code page 437 doesn't contain it.
+#[must_use]
+pub fn encode(character: char) -> Option<u8> {
+    if character == ' ' || character.is_ascii_graphic() {
+        return Some(character as u8);
+    }
+
+    CHARACTERS
+        .iter()
+        .position(|&candidate| candidate == character)
+        .map(|index| index as u8)
+}
+
+/// Like `encode`, but `None` for the characters whose bytes terminals treat as control characters.
+#[must_use]
+pub fn encode_printable(character: char) -> Option<u8> {
+    encode(character).filter(|byte| !byte.is_ascii_control())
+}
+
+/// Encodes text for a terminal using code page 437.
+/// Ascii characters, including control characters and thereby escape codes, are kept as they are
+/// and characters without a printable equivalent are replaced with `?`.
+#[must_use]
+pub fn encode_str(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|character| {
+            if character.is_ascii() {
+                character as u8
+            } else {
+                encode_printable(character).unwrap_or(b'?')
+            }
+        })
+        .collect()
+}
+
+impl<C: Color + PartialEq> AsciiImage<C> {
+    /// Formats the image like `to_string`, but encoded in code page 437 instead of UTF-8,
+    /// for terminals such as the Linux VGA console and BBS software.
+    #[must_use]
+    pub fn to_cp437(&self) -> Vec<u8> {
+        encode_str(&self.to_string())
+    }
+}
@@ -0,0 +1,566 @@
+use crate::cell::{AsciiCell, Attributes, Foreground};
+use crate::image::AsciiImage;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+const SIGNATURE: &str = "flf2a";
+const STANDARD: &str = include_str!("figlet/standard.flf");
+
+/// The characters every FIGfont has to define (in order) before any code tagged characters.
+const REQUIRED_CHARACTERS: [char; 102] = {
+    let mut characters = ['\0'; 102];
+
+    let mut index = 0;
+    while index < 95 {
+        characters[index] = (b' ' + index as u8) as char;
+        index += 1;
+    }
+
+    characters[95] = 'Ä';
+    characters[96] = 'Ö';
+    characters[97] = 'Ü';
+    characters[98] = 'ä';
+    characters[99] = 'ö';
+    characters[100] = 'ü';
+    characters[101] = 'ß';
+
+    characters
+};
+
+/// The bit set in the full layout when horizontal fitting (kerning) is enabled.
+const FULL_LAYOUT_FITTING: u32 = 64;
+/// The bit set in the full layout when horizontal smushing is enabled.
+const FULL_LAYOUT_SMUSHING: u32 = 128;
+
+/// A set of horizontal smushing rules as defined by the FIGfont specification.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct SmushingRules(u8);
+
+impl SmushingRules {
+    /// No rules, meaning "universal smushing": the later character simply replaces the earlier one.
+    pub const UNIVERSAL: SmushingRules = SmushingRules(0);
+    /// Two equal characters are smushed into one.
+    pub const EQUAL_CHARACTER: SmushingRules = SmushingRules(1);
+    /// An underscore is replaced by any of `|/\[]{}()<>`.
+    pub const UNDERSCORE: SmushingRules = SmushingRules(2);
+    /// Of the classes `|`, `/\`, `[]`, `{}`, `()` and `<>`, the latter one wins.
+    pub const HIERARCHY: SmushingRules = SmushingRules(4);
+    /// Opposing brackets, braces and parentheses are replaced by `|`.
+    pub const OPPOSITE_PAIR: SmushingRules = SmushingRules(8);
+    /// `/\` becomes `|`, `\/` becomes `Y` and `><` becomes `X`.
+    pub const BIG_X: SmushingRules = SmushingRules(16);
+    /// Two hardblanks are smushed into one.
+    pub const HARDBLANK: SmushingRules = SmushingRules(32);
+
+    /// Constructs a rule set from the bits used in FIGfont headers.
+    /// Bits other than the six lowest are ignored.
+    #[must_use]
+    pub const fn from_bits(bits: u8) -> SmushingRules {
+        SmushingRules(bits & 0b11_1111)
+    }
+
+    /// The bits used in FIGfont headers.
+    #[must_use]
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// Whether all rules in `other` are in `self`.
+    #[must_use]
+    pub const fn contains(&self, other: SmushingRules) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The union of the two rule sets.
+    #[must_use]
+    pub const fn union(self, other: SmushingRules) -> SmushingRules {
+        SmushingRules(self.0 | other.0)
+    }
+}
+
+/// How characters are placed next to each other.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Layout {
+    /// Every character keeps its full width.
+    FullWidth,
+    /// Characters are moved together until they touch.
+    Fitting,
+    /// Characters are moved together until they overlap by one sub-character,
+    /// which is then smushed according to the rules.
+    Smushing(SmushingRules),
+}
+
+/// An error that occurred while loading a FIGfont.
+#[derive(Debug)]
+pub enum FigletError {
+    /// The font file couldn't be read.
+    Io(std::io::Error),
+    /// The first line isn't a valid FIGfont header.
+    InvalidHeader,
+    /// The font ended before all lines of the character were read.
+    UnexpectedEnd(char),
+    /// The line that should begin a code tagged character couldn't be parsed.
+    InvalidCodeTag(String),
+}
+
+impl Display for FigletError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FigletError::Io(error) => write!(f, "failed to read font: {error}"),
+            FigletError::InvalidHeader => write!(f, "invalid FIGfont header"),
+            FigletError::UnexpectedEnd(character) => {
+                write!(f, "font ended in the middle of character {character:?}")
+            }
+            FigletError::InvalidCodeTag(line) => write!(f, "invalid code tag {line:?}"),
+        }
+    }
+}
+
+impl Error for FigletError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FigletError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for FigletError {
+    fn from(error: std::io::Error) -> Self {
+        FigletError::Io(error)
+    }
+}
+
+/// A FIGlet font (`.flf`), used to render text as large banners.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FigletFont {
+    /// The sub-character which is rendered as a space but is never smushed.
+    hardblank: char,
+    /// The number of rows every character has.
+    height: usize,
+    layout: Layout,
+    right_to_left: bool,
+    /// The rows of every character, all padded to the same width.
+    characters: HashMap<char, Vec<Vec<char>>>,
+}
+
+impl FigletFont {
+    /// The "standard" FIGlet font, which is bundled with the crate.
+    #[must_use]
+    pub fn standard() -> FigletFont {
+        // The bundled font is known to be valid.
+        STANDARD.parse().unwrap()
+    }
+
+    /// Reads and parses a `.flf` file.
+    ///
+    /// # Errors
+    /// If the file can't be read or isn't a valid FIGfont.
+    pub fn open(path: impl AsRef<Path>) -> Result<FigletFont, FigletError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// The number of rows every rendered line of text takes up.
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The layout used when placing characters next to each other.
+    #[must_use]
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Overrides the layout that the font specifies.
+    #[must_use]
+    pub fn with_layout(mut self, layout: Layout) -> FigletFont {
+        self.layout = layout;
+        self
+    }
+
+    /// Lays out `text` and returns the resulting rows.
+    /// Every line of `text` is rendered as `height` rows.
+    /// Characters not in the font are skipped.
+    #[must_use]
+    pub fn render_rows(&self, text: &str) -> Vec<String> {
+        let mut rows = Vec::new();
+
+        for line in text.lines() {
+            for row in self.render_line(line) {
+                rows.push(
+                    row.into_iter()
+                        .map(|character| if character == self.hardblank { ' ' } else { character })
+                        .collect(),
+                );
+            }
+        }
+
+        rows
+    }
+
+    /// Lays out `text` and draws it into an image.
+    /// The characters are colored with `foreground`, and the whole image with `background`.
+    /// Blank cells have no foreground,
+    /// which means that they are see-through when used with [`AsciiImage::overlay`].
+    #[must_use]
+    pub fn render<C: Clone>(&self, text: &str, foreground: C, background: C) -> AsciiImage<C> {
+        let rows = self.render_rows(text);
+
+        let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+        let width = width.try_into().unwrap_or(u32::MAX);
+        let height = rows.len().try_into().unwrap_or(u32::MAX);
+
+        let blank = AsciiCell {
+            background,
+            foreground: None,
+            attributes: Attributes::NONE,
+        };
+        let mut image = AsciiImage::filled(width, height, blank);
+
+        for (row, y) in rows.iter().zip(0..) {
+            for (character, x) in row.chars().zip(0..) {
+                if character == ' ' {
+                    continue;
+                }
+
+                if let Some(cell) = image.get_mut(x, y) {
+                    cell.foreground = Some(Foreground {
+                        color: foreground.clone(),
+                        character,
+                    });
+                }
+            }
+        }
+
+        image
+    }
+
+    /// Lays out a single line of text, returning `height` rows of equal width.
+    fn render_line(&self, line: &str) -> Vec<Vec<char>> {
+        let mut rows = vec![Vec::new(); self.height];
+        let mut previous_width = 0;
+
+        let glyphs = line.chars().filter_map(|character| {
+            self.characters
+                .get(&character)
+                .or_else(|| self.characters.get(&'\0'))
+        });
+
+        for glyph in glyphs {
+            let width = glyph.first().map_or(0, Vec::len);
+            // Characters narrower than two columns are only ever fitted.
+            let can_smush = 2 <= previous_width && 2 <= width;
+
+            rows = if self.right_to_left {
+                self.append(glyph, &rows, can_smush, true)
+            } else {
+                self.append(&rows, glyph, can_smush, false)
+            };
+
+            previous_width = width;
+        }
+
+        rows
+    }
+
+    /// Places `right` after `left`, overlapping them as much as the layout allows.
+    /// `later_is_left` decides which character wins in universal smushing.
+    fn append(
+        &self,
+        left: &[Vec<char>],
+        right: &[Vec<char>],
+        can_smush: bool,
+        later_is_left: bool,
+    ) -> Vec<Vec<char>> {
+        let overlap = self.overlap(left, right, can_smush, later_is_left);
+
+        left.iter()
+            .zip(right)
+            .map(|(left, right)| {
+                let overlap_start = left.len().saturating_sub(overlap);
+                let skipped = overlap.saturating_sub(left.len());
+
+                let mut row = left[..overlap_start].to_vec();
+
+                for (left, right) in left[overlap_start..].iter().zip(&right[skipped..overlap]) {
+                    row.push(
+                        self.smush(*left, *right, can_smush, later_is_left)
+                            .unwrap_or(*right),
+                    );
+                }
+
+                row.extend_from_slice(&right[overlap..]);
+                row
+            })
+            .collect()
+    }
+
+    /// How many columns `right` can be moved into `left`.
+    fn overlap(
+        &self,
+        left: &[Vec<char>],
+        right: &[Vec<char>],
+        can_smush: bool,
+        later_is_left: bool,
+    ) -> usize {
+        if self.layout == Layout::FullWidth {
+            return 0;
+        }
+
+        let mut overlap = right.first().map_or(0, Vec::len);
+
+        for (left, right) in left.iter().zip(right) {
+            let trailing = left.iter().rev().take_while(|&&character| character == ' ').count();
+            let leading = right.iter().take_while(|&&character| character == ' ').count();
+
+            let mut row_overlap = trailing + leading;
+
+            let last = left.len().checked_sub(trailing + 1).map(|index| left[index]);
+            let first = right.get(leading).copied();
+
+            if let (Some(last), Some(first)) = (last, first) {
+                if self.smush(last, first, can_smush, later_is_left).is_some() {
+                    row_overlap += 1;
+                }
+            }
+
+            overlap = overlap.min(row_overlap);
+        }
+
+        overlap
+    }
+
+    /// Smushes two sub-characters into one, or returns `None` if they can't be smushed.
+    fn smush(&self, left: char, right: char, can_smush: bool, later_is_left: bool) -> Option<char> {
+        if left == ' ' {
+            return Some(right);
+        }
+        if right == ' ' {
+            return Some(left);
+        }
+
+        let Layout::Smushing(rules) = self.layout else {
+            return None;
+        };
+
+        if !can_smush {
+            return None;
+        }
+
+        let hardblank = self.hardblank;
+
+        if rules == SmushingRules::UNIVERSAL {
+            return Some(if left == hardblank {
+                right
+            } else if right == hardblank || later_is_left {
+                left
+            } else {
+                right
+            });
+        }
+
+        if rules.contains(SmushingRules::HARDBLANK) && left == hardblank && right == hardblank {
+            return Some(left);
+        }
+
+        if left == hardblank || right == hardblank {
+            return None;
+        }
+
+        if rules.contains(SmushingRules::EQUAL_CHARACTER) && left == right {
+            return Some(left);
+        }
+
+        if rules.contains(SmushingRules::UNDERSCORE) {
+            const REPLACEMENTS: &str = "|/\\[]{}()<>";
+
+            if left == '_' && REPLACEMENTS.contains(right) {
+                return Some(right);
+            }
+            if right == '_' && REPLACEMENTS.contains(left) {
+                return Some(left);
+            }
+        }
+
+        if rules.contains(SmushingRules::HIERARCHY) {
+            const CLASSES: [&str; 6] = ["|", "/\\", "[]", "{}", "()", "<>"];
+
+            let class = |character| CLASSES.iter().position(|class| class.contains(character));
+
+            if let (Some(left_class), Some(right_class)) = (class(left), class(right)) {
+                if left_class < right_class {
+                    return Some(right);
+                }
+                if right_class < left_class {
+                    return Some(left);
+                }
+            }
+        }
+
+        if rules.contains(SmushingRules::OPPOSITE_PAIR) {
+            if let ('[', ']') | (']', '[') | ('{', '}') | ('}', '{') | ('(', ')') | (')', '(') =
+                (left, right)
+            {
+                return Some('|');
+            }
+        }
+
+        if rules.contains(SmushingRules::BIG_X) {
+            match (left, right) {
+                ('/', '\\') => return Some('|'),
+                ('\\', '/') => return Some('Y'),
+                ('>', '<') => return Some('X'),
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
+
+impl FromStr for FigletFont {
+    type Err = FigletError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut lines = source.lines();
+
+        let header = lines.next().ok_or(FigletError::InvalidHeader)?;
+        let header = header.strip_prefix(SIGNATURE).ok_or(FigletError::InvalidHeader)?;
+
+        let mut header_chars = header.chars();
+        let hardblank = header_chars.next().ok_or(FigletError::InvalidHeader)?;
+
+        let parameters = header_chars
+            .as_str()
+            .split_whitespace()
+            .map(str::parse::<i64>)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| FigletError::InvalidHeader)?;
+
+        // height, baseline, max length, old layout and comment lines are mandatory.
+        let [height, _baseline, _max_length, old_layout, comment_lines, ..] = parameters[..] else {
+            return Err(FigletError::InvalidHeader);
+        };
+        let print_direction = parameters.get(5).copied().unwrap_or(0);
+        let full_layout = parameters.get(6).copied();
+
+        let height = usize::try_from(height).map_err(|_| FigletError::InvalidHeader)?;
+        let comment_lines = usize::try_from(comment_lines).map_err(|_| FigletError::InvalidHeader)?;
+
+        let layout = match full_layout {
+            Some(full_layout) => {
+                let full_layout = u32::try_from(full_layout).map_err(|_| FigletError::InvalidHeader)?;
+                let rules = SmushingRules::from_bits(full_layout as u8);
+
+                if full_layout & FULL_LAYOUT_SMUSHING != 0 {
+                    Layout::Smushing(rules)
+                } else if full_layout & FULL_LAYOUT_FITTING != 0 {
+                    Layout::Fitting
+                } else {
+                    Layout::FullWidth
+                }
+            }
+            None => match old_layout {
+                ..=-1 => Layout::FullWidth,
+                0 => Layout::Fitting,
+                rules => Layout::Smushing(SmushingRules::from_bits(rules as u8)),
+            },
+        };
+
+        let mut lines = lines.skip(comment_lines).peekable();
+        let mut characters = HashMap::new();
+
+        for character in REQUIRED_CHARACTERS {
+            // Some fonts omit the German characters.
+            if lines.peek().is_none() && character == 'Ä' {
+                break;
+            }
+
+            characters.insert(character, read_character(&mut lines, height, character)?);
+        }
+
+        while let Some(line) = lines.next() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let code = line.split_whitespace().next().unwrap_or_default();
+            let code =
+                parse_code(code).ok_or_else(|| FigletError::InvalidCodeTag(line.to_string()))?;
+
+            match char::from_u32(code) {
+                Some(character) => {
+                    characters.insert(character, read_character(&mut lines, height, character)?);
+                }
+                // Negative and otherwise invalid codes are still followed by a character.
+                None => {
+                    for _ in 0..height {
+                        lines.next();
+                    }
+                }
+            }
+        }
+
+        Ok(FigletFont {
+            hardblank,
+            height,
+            layout,
+            right_to_left: print_direction == 1,
+            characters,
+        })
+    }
+}
+
+/// Reads the `height` rows of `character` and pads them to the same width.
+fn read_character<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    height: usize,
+    character: char,
+) -> Result<Vec<Vec<char>>, FigletError> {
+    let mut rows = Vec::with_capacity(height);
+
+    for _ in 0..height {
+        let line = lines.next().ok_or(FigletError::UnexpectedEnd(character))?;
+        rows.push(strip_endmark(line).chars().collect::<Vec<_>>());
+    }
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    for row in &mut rows {
+        row.resize(width, ' ');
+    }
+
+    Ok(rows)
+}
+
+/// Removes trailing whitespace and the endmark (the last character, possibly repeated).
+fn strip_endmark(line: &str) -> &str {
+    let line = line.trim_end();
+
+    match line.chars().last() {
+        Some(endmark) => line.trim_end_matches(endmark),
+        None => line,
+    }
+}
+
+/// Parses a decimal, hexadecimal (`0x`) or octal (`0`) character code.
+/// Negative codes are mapped to values which aren't valid characters.
+fn parse_code(code: &str) -> Option<u32> {
+    let (negative, code) = match code.strip_prefix('-') {
+        Some(code) => (true, code),
+        None => (false, code),
+    };
+
+    let value = if let Some(hex) = code.strip_prefix("0x").or_else(|| code.strip_prefix("0X")) {
+        u32::from_str_radix(hex, 16).ok()?
+    } else if code.len() > 1 && code.starts_with('0') {
+        u32::from_str_radix(&code[1..], 8).ok()?
+    } else {
+        code.parse().ok()?
+    };
+
+    Some(if negative { u32::MAX } else { value })
+}
@@ -0,0 +1,334 @@
+//! The `.aeski` binary format for saving ascii images.
+//!
+//! A file starts with the magic bytes `AESKI`, the version of the format,
+//! the kind of palette used (see [`FileColor::PALETTE`]),
+//! and the width and height of the image as little endian `u32`s.
+//! The cells follow, left to right, wrapping from top to bottom, run-length encoded:
+//! every run is a LEB128 encoded count, which is at least one, followed by the cell which is repeated.
+//!
+//! A cell is stored as a byte with the bits of its attributes
+//! (the highest bit set if it has a foreground), its background color,
+//! and, if it has a foreground, its character as UTF-8 followed by its foreground color.
+
+use crate::cell::{AsciiCell, Attributes, Foreground};
+use crate::color::ansi_24_bit::Ansi24Bit;
+use crate::color::ansi_3_bit::Ansi3Bit;
+use crate::color::ansi_4_bit::Ansi4Bit;
+use crate::color::ansi_8_bit::cube::Cube;
+use crate::color::ansi_8_bit::grayscale::Grayscale;
+use crate::color::ansi_8_bit::Ansi8Bit;
+use crate::color::colorless::Colorless;
+use crate::color::compact::CompactColor;
+use crate::color::mirc::{self, Mirc};
+use crate::color::Color;
+use crate::image::AsciiImage;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 5] = b"AESKI";
+const VERSION: u8 = 1;
+
+/// Set in the attribute byte of a cell if it has a foreground.
+const HAS_FOREGROUND: u8 = 1 << 7;
+
+/// A color which can be stored in `.aeski` files.
+pub trait FileColor: Color {
+    /// Identifies the palette in the header, so that files aren't loaded as the wrong color type.
+    const PALETTE: u8;
+
+    /// Appends the color to `bytes`.
+    fn encode(&self, bytes: &mut Vec<u8>);
+
+    /// Reads a color from the start of `bytes` and advances past it.
+    /// Returns `None` if `bytes` doesn't start with a valid color.
+    fn decode(bytes: &mut &[u8]) -> Option<Self>
+    where
+        Self: Sized;
+}
+
+/// Implements `FileColor` for a color with `u8` as its compact representation,
+/// where the valid values are less than `$count`.
+macro_rules! store_as_index {
+    ($color:ty, $palette:expr, $count:expr) => {
+        impl FileColor for $color {
+            const PALETTE: u8 = $palette;
+
+            fn encode(&self, bytes: &mut Vec<u8>) {
+                bytes.push(self.compact());
+            }
+
+            fn decode(bytes: &mut &[u8]) -> Option<Self> {
+                let index = take_byte(bytes)?;
+                ((index as u16) < $count).then(|| <$color>::expand(index))
+            }
+        }
+    };
+}
+
+store_as_index!(Ansi3Bit, 1, 8);
+store_as_index!(Ansi4Bit, 2, 16);
+store_as_index!(Ansi8Bit, 3, 256);
+store_as_index!(Cube, 5, 216);
+store_as_index!(Grayscale, 6, 24);
+store_as_index!(Mirc, 7, mirc::COUNT as u16);
+
+impl FileColor for Colorless {
+    const PALETTE: u8 = 0;
+
+    fn encode(&self, _: &mut Vec<u8>) {}
+
+    fn decode(_: &mut &[u8]) -> Option<Self> {
+        Some(Colorless)
+    }
+}
+
+impl FileColor for Ansi24Bit {
+    const PALETTE: u8 = 4;
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.extend(self.compact());
+    }
+
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        let ([r, g, b], rest) = bytes.split_first_chunk()?;
+        *bytes = rest;
+        Some(Ansi24Bit::new(*r, *g, *b))
+    }
+}
+
+/// An error that occurred while loading an `.aeski` file.
+#[derive(Debug)]
+pub enum FileError {
+    /// The file couldn't be read.
+    Io(std::io::Error),
+    /// The file doesn't start with the magic bytes.
+    NotAeski,
+    /// The file was written by a newer version of the format.
+    UnsupportedVersion(u8),
+    /// The file stores colors of a different type than requested.
+    WrongPalette { expected: u8, found: u8 },
+    /// The file ended early or contains invalid data.
+    Corrupt,
+}
+
+impl Display for FileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileError::Io(error) => write!(f, "failed to read file: {error}"),
+            FileError::NotAeski => write!(f, "not an aeski file"),
+            FileError::UnsupportedVersion(version) => write!(f, "unsupported version {version}"),
+            FileError::WrongPalette { expected, found } => {
+                write!(f, "expected palette {expected}, but the file uses palette {found}")
+            }
+            FileError::Corrupt => write!(f, "the file is corrupt"),
+        }
+    }
+}
+
+impl Error for FileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FileError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for FileError {
+    fn from(error: std::io::Error) -> Self {
+        FileError::Io(error)
+    }
+}
+
+impl<C: FileColor + PartialEq> AsciiImage<C> {
+    /// Encodes the image in the `.aeski` format.
+    #[must_use]
+    pub fn to_aeski_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend(MAGIC);
+        bytes.push(VERSION);
+        bytes.push(C::PALETTE);
+        bytes.extend(self.width().to_le_bytes());
+        bytes.extend(self.height().to_le_bytes());
+
+        for run in self.cells().chunk_by(|a, b| a == b) {
+            write_varint(&mut bytes, run.len() as u64);
+            encode_cell(&run[0], &mut bytes);
+        }
+
+        bytes
+    }
+
+    /// Decodes an image in the `.aeski` format.
+    ///
+    /// # Errors
+    /// If `bytes` isn't a valid `.aeski` file with colors of type `C`,
+    /// or the image doesn't fit in memory.
+    pub fn from_aeski_bytes(mut bytes: &[u8]) -> Result<Self, FileError> {
+        let bytes = &mut bytes;
+
+        if bytes.split_off(..MAGIC.len()) != Some(MAGIC) {
+            return Err(FileError::NotAeski);
+        }
+
+        let version = take_byte(bytes).ok_or(FileError::Corrupt)?;
+        if version != VERSION {
+            return Err(FileError::UnsupportedVersion(version));
+        }
+
+        let palette = take_byte(bytes).ok_or(FileError::Corrupt)?;
+        if palette != C::PALETTE {
+            return Err(FileError::WrongPalette {
+                expected: C::PALETTE,
+                found: palette,
+            });
+        }
+
+        let width = take_u32(bytes).ok_or(FileError::Corrupt)?;
+        let height = take_u32(bytes).ok_or(FileError::Corrupt)?;
+        let area = width as u64 * height as u64;
+
+        let mut cells = Vec::new();
+
+        while !bytes.is_empty() {
+            let count = read_varint(bytes).ok_or(FileError::Corrupt)?;
+            let cell = decode_cell(bytes).ok_or(FileError::Corrupt)?;
+
+            // Empty runs aren't written, and more cells than the image can hold aren't allocated.
+            if count == 0 || area - (cells.len() as u64) < count {
+                return Err(FileError::Corrupt);
+            }
+
+            // The dimensions come from the file, so they may be far too large to allocate.
+            let count = usize::try_from(count).map_err(|_| FileError::Corrupt)?;
+            cells.try_reserve(count).map_err(|_| FileError::Corrupt)?;
+            cells.extend(std::iter::repeat_n(cell, count));
+        }
+
+        AsciiImage::from_cells(cells, width, height).map_err(|_| FileError::Corrupt)
+    }
+
+    /// Writes the image in the `.aeski` format.
+    ///
+    /// # Errors
+    /// If writing fails.
+    pub fn write_aeski(&self, mut to: impl Write) -> std::io::Result<()> {
+        to.write_all(&self.to_aeski_bytes())
+    }
+
+    /// Reads an image in the `.aeski` format.
+    ///
+    /// # Errors
+    /// If reading fails or the data isn't a valid `.aeski` file with colors of type `C`.
+    pub fn read_aeski(mut from: impl Read) -> Result<Self, FileError> {
+        let mut bytes = Vec::new();
+        from.read_to_end(&mut bytes)?;
+        Self::from_aeski_bytes(&bytes)
+    }
+
+    /// Saves the image to a `.aeski` file.
+    ///
+    /// # Errors
+    /// If the file can't be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_aeski(&mut file)?;
+        file.flush()
+    }
+
+    /// Loads an image from a `.aeski` file.
+    ///
+    /// # Errors
+    /// If the file can't be read or isn't a valid `.aeski` file with colors of type `C`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        Self::read_aeski(BufReader::new(File::open(path)?))
+    }
+}
+
+fn encode_cell<C: FileColor>(cell: &AsciiCell<C>, bytes: &mut Vec<u8>) {
+    let flags = cell.attributes.bits();
+
+    match &cell.foreground {
+        Some(foreground) => {
+            bytes.push(flags | HAS_FOREGROUND);
+            cell.background.encode(bytes);
+
+            let mut character = [0; 4];
+            bytes.extend(foreground.character.encode_utf8(&mut character).as_bytes());
+            foreground.color.encode(bytes);
+        }
+        None => {
+            bytes.push(flags);
+            cell.background.encode(bytes);
+        }
+    }
+}
+
+fn decode_cell<C: FileColor>(bytes: &mut &[u8]) -> Option<AsciiCell<C>> {
+    let flags = take_byte(bytes)?;
+    let background = C::decode(bytes)?;
+
+    let foreground = if flags & HAS_FOREGROUND != 0 {
+        let character = take_char(bytes)?;
+        let color = C::decode(bytes)?;
+        Some(Foreground { color, character })
+    } else {
+        None
+    };
+
+    Some(AsciiCell {
+        background,
+        foreground,
+        attributes: Attributes::from_bits(flags),
+    })
+}
+
+fn take_byte(bytes: &mut &[u8]) -> Option<u8> {
+    bytes.split_off_first().copied()
+}
+
+fn take_u32(bytes: &mut &[u8]) -> Option<u32> {
+    bytes.split_off(..4).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap_or_default()))
+}
+
+/// Reads a UTF-8 encoded character.
+fn take_char(bytes: &mut &[u8]) -> Option<char> {
+    let length = match bytes.first()? {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        _ => 4,
+    };
+
+    let character = std::str::from_utf8(bytes.split_off(..length)?).ok()?;
+    character.chars().next()
+}
+
+/// Writes `value` in LEB128.
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while 0x80 <= value {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads a LEB128 encoded value.
+fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0_u64;
+
+    for shift in (0..64).step_by(7) {
+        let byte = take_byte(bytes)?;
+        value |= ((byte & 0x7F) as u64).checked_shl(shift)?;
+
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,209 @@
+use crate::color::util::{decode, encode, to_rgba8};
+use image::{GenericImageView, Rgb};
+use std::f64::consts::PI;
+
+/// The number of lobes of the Lanczos kernel.
+const LANCZOS_LOBES: f64 = 3.0;
+/// How many standard deviations the Gaussian kernel reaches.
+const GAUSSIAN_RADIUS: f64 = 3.0;
+
+/// How the pixels under a cell are combined into the color that the cell approximates.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum Filter {
+    /// The average of the pixels whose top left corners lie in the cell.
+    /// The cell's bounds are rounded down to whole pixels.
+    #[default]
+    Box,
+    /// The average of the pixels in the cell, weighted by how much of each pixel is covered.
+    Area,
+    /// The pixel under the center of the cell.
+    Center,
+    /// A Gaussian weighted average, with a standard deviation of half the cell's size.
+    Gaussian,
+    /// A Lanczos (windowed sinc) weighted average reaching three cells in each direction.
+    /// Preserves detail better than the other averages, but may overshoot at sharp edges.
+    Lanczos,
+    /// The median of each channel of the pixels in the cell,
+    /// which keeps thin lines and noise from bleeding into it.
+    Median,
+}
+
+impl Filter {
+    /// Samples the color of the rectangle spanning from (`left`, `top`) to (`right`, `bottom`),
+    /// measured in pixels.
+    /// If `linear_light` is set, averages are computed in linear light.
+    /// An image without pixels is sampled as black.
+    ///
+    /// Returns `None` for `Filter::Box`, whose pixels are passed to the renderer as they are
+    /// (see [`CellRenderer::render_cell`](crate::renderer::CellRenderer::render_cell)).
+    pub(crate) fn sample<I: GenericImageView>(
+        &self,
+        image: &I,
+        (left, top): (f64, f64),
+        (right, bottom): (f64, f64),
+        linear_light: bool,
+    ) -> Option<Rgb<u8>> {
+        let (width, height) = image.dimensions();
+
+        if width == 0 || height == 0 {
+            return Some(Rgb([0; 3]));
+        }
+
+        let center_x = (left + right) / 2.0;
+        let center_y = (top + bottom) / 2.0;
+        let size_x = (right - left).max(f64::EPSILON);
+        let size_y = (bottom - top).max(f64::EPSILON);
+
+        let mut sampler = Sampler::new(image, linear_light);
+
+        match self {
+            Filter::Box => return None,
+            Filter::Area => sampler.add_rectangle(left, top, right, bottom, |x, y| {
+                let overlap = |position: f64, start: f64, end: f64| {
+                    (end.min(position + 1.0) - start.max(position)).max(0.0)
+                };
+                overlap(x, left, right) * overlap(y, top, bottom)
+            }),
+            Filter::Center => sampler.add(center_x as u32, center_y as u32, 1.0),
+            Filter::Gaussian => {
+                let sigma_x = size_x / 2.0;
+                let sigma_y = size_y / 2.0;
+
+                sampler.add_rectangle(
+                    center_x - GAUSSIAN_RADIUS * sigma_x,
+                    center_y - GAUSSIAN_RADIUS * sigma_y,
+                    center_x + GAUSSIAN_RADIUS * sigma_x,
+                    center_y + GAUSSIAN_RADIUS * sigma_y,
+                    |x, y| {
+                        let dx = (x + 0.5 - center_x) / sigma_x;
+                        let dy = (y + 0.5 - center_y) / sigma_y;
+                        (-(dx * dx + dy * dy) / 2.0).exp()
+                    },
+                )
+            }
+            Filter::Lanczos => sampler.add_rectangle(
+                center_x - LANCZOS_LOBES * size_x,
+                center_y - LANCZOS_LOBES * size_y,
+                center_x + LANCZOS_LOBES * size_x,
+                center_y + LANCZOS_LOBES * size_y,
+                |x, y| lanczos((x + 0.5 - center_x) / size_x) * lanczos((y + 0.5 - center_y) / size_y),
+            ),
+            Filter::Median => {
+                let left = (left.floor() as u32).min(width - 1);
+                let top = (top.floor() as u32).min(height - 1);
+                let right = (right.ceil() as u32).clamp(left + 1, width);
+                let bottom = (bottom.ceil() as u32).clamp(top + 1, height);
+
+                return Some(median(image, left..right, top..bottom));
+            }
+        }
+
+        let color = sampler.finish().unwrap_or_else(|| {
+            let x = (center_x as u32).min(width - 1);
+            let y = (center_y as u32).min(height - 1);
+            let [r, g, b, _] = to_rgba8(image.get_pixel(x, y)).0;
+            Rgb([r, g, b])
+        });
+
+        Some(color)
+    }
+}
+
+/// The Lanczos kernel.
+fn lanczos(x: f64) -> f64 {
+    let sinc = |x: f64| if x == 0.0 { 1.0 } else { (PI * x).sin() / (PI * x) };
+
+    if x.abs() < LANCZOS_LOBES {
+        sinc(x) * sinc(x / LANCZOS_LOBES)
+    } else {
+        0.0
+    }
+}
+
+fn median(image: &impl GenericImageView, xs: std::ops::Range<u32>, ys: std::ops::Range<u32>) -> Rgb<u8> {
+    let mut channels: [Vec<u8>; 3] = Default::default();
+
+    for y in ys {
+        for x in xs.clone() {
+            let [r, g, b, _] = to_rgba8(image.get_pixel(x, y)).0;
+            for (channel, value) in channels.iter_mut().zip([r, g, b]) {
+                channel.push(value);
+            }
+        }
+    }
+
+    Rgb(channels.map(|mut channel| {
+        let middle = channel.len() / 2;
+        *channel.select_nth_unstable(middle).1
+    }))
+}
+
+/// Accumulates a weighted average of pixels.
+struct Sampler<'a, I> {
+    image: &'a I,
+    linear_light: bool,
+    sum: [f64; 3],
+    weight: f64,
+}
+
+impl<'a, I: GenericImageView> Sampler<'a, I> {
+    fn new(image: &'a I, linear_light: bool) -> Self {
+        Sampler {
+            image,
+            linear_light,
+            sum: [0.0; 3],
+            weight: 0.0,
+        }
+    }
+
+    fn add(&mut self, x: u32, y: u32, weight: f64) {
+        let x = x.min(self.image.width() - 1);
+        let y = y.min(self.image.height() - 1);
+
+        let [r, g, b, _] = to_rgba8(self.image.get_pixel(x, y)).0;
+
+        for (sum, channel) in self.sum.iter_mut().zip([r, g, b]) {
+            let channel = if self.linear_light {
+                decode(channel) as f64 / u16::MAX as f64
+            } else {
+                channel as f64 / u8::MAX as f64
+            };
+
+            *sum += weight * channel;
+        }
+        self.weight += weight;
+    }
+
+    /// Adds every pixel which overlaps the rectangle, weighted by `weight(x, y)`.
+    /// Pixels outside the image are clamped to its edges.
+    fn add_rectangle(&mut self, left: f64, top: f64, right: f64, bottom: f64, weight: impl Fn(f64, f64) -> f64) {
+        for y in top.floor() as i64..bottom.ceil() as i64 {
+            for x in left.floor() as i64..right.ceil() as i64 {
+                let weight = weight(x as f64, y as f64);
+
+                if weight != 0.0 {
+                    self.add(x.max(0) as u32, y.max(0) as u32, weight);
+                }
+            }
+        }
+    }
+
+    /// The weighted average, or `None` if nothing was added.
+    fn finish(self) -> Option<Rgb<u8>> {
+        if self.weight <= 0.0 {
+            return None;
+        }
+
+        let linear_light = self.linear_light;
+
+        Some(Rgb(self.sum.map(|sum| {
+            let channel = (sum / self.weight).clamp(0.0, 1.0);
+
+            if linear_light {
+                encode((channel * u16::MAX as f64).round() as u16)
+            } else {
+                (channel * u8::MAX as f64).round() as u8
+            }
+        })))
+    }
+}
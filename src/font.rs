@@ -14,6 +14,22 @@ pub struct Font<G> {
     aspect_ratio: Ratio<u32>,
 }
 
+impl<G> Font<G> {
+    /// Constructs a font without checking its arguments, for the fonts defined as constants.
+    /// The gradient must not be empty, `max_coverage` must be at most 1 and `aspect_ratio` must not be 0.
+    pub(crate) const fn new_unchecked(
+        gradient: G,
+        max_coverage: Ratio<u32>,
+        aspect_ratio: Ratio<u32>,
+    ) -> Font<G> {
+        Font {
+            gradient,
+            max_coverage,
+            aspect_ratio,
+        }
+    }
+}
+
 impl<G: AsRef<[char]>> Font<G> {
     /// Tries to construct a new `Font` object.
     ///
@@ -1,14 +1,23 @@
 use crate::cell::AsciiCell;
 use crate::color::Color;
+use crate::conversion::Conversion;
 use crate::font::Font;
+use crate::image::convert::Converter;
+use crate::parallel::{MaybeSend, MaybeSync};
+use crate::renderer::{CellRenderer, GradientMatcher};
 use crate::sgr::SelectGraphicRendition;
-use image::{DynamicImage, GenericImageView};
+use crate::sizing::{scaled_dimensions, Sizing};
+use image::GenericImageView;
 use num_rational::Ratio;
-use rayon::iter::IntoParallelIterator;
-use rayon::iter::ParallelIterator;
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 
+mod compose;
+mod convert;
+mod html;
+mod plain;
+mod transform;
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct AsciiImage<Color> {
     width: u32,
@@ -45,15 +54,34 @@ impl<C> AsciiImage<C> {
         }
     }
 
+    /// Creates an image with the provided dimensions where every cell is `cell`.
+    #[must_use]
+    pub fn filled(width: u32, height: u32, cell: AsciiCell<C>) -> Self
+    where
+        C: Clone,
+    {
+        let area = width as u64 * height as u64;
+
+        AsciiImage {
+            width,
+            cells: vec![cell; area.try_into().unwrap_or(usize::MAX)],
+        }
+    }
+
     /// The width of the image in characters
     #[must_use]
     pub const fn width(&self) -> u32 {
         self.width
     }
 
-    /// The height of the image in characters
+    /// The height of the image in characters.
+    /// An image without width has no cells, so its height is 0.
     #[must_use]
     pub fn height(&self) -> u32 {
+        if self.width == 0 {
+            return 0;
+        }
+
         (self.cells.len() as u64 / self.width as u64).try_into().unwrap_or(u32::MAX)
     }
 
@@ -69,87 +97,461 @@ impl<C> AsciiImage<C> {
     pub fn cells_mut(&mut self) -> &mut [AsciiCell<C>] {
         &mut self.cells
     }
+
+    /// The index into `cells` of the cell at (`x`, `y`),
+    /// or `None` if the coordinates are out of bounds.
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        if self.width <= x || self.height() <= y {
+            return None;
+        }
+
+        (self.width as u64 * y as u64 + x as u64).try_into().ok()
+    }
+
+    /// Gets the cell at (`x`, `y`), or `None` if the coordinates are out of bounds.
+    #[must_use]
+    pub fn get(&self, x: u32, y: u32) -> Option<&AsciiCell<C>> {
+        self.cells.get(self.index(x, y)?)
+    }
+
+    /// Gets the cell at (`x`, `y`), or `None` if the coordinates are out of bounds.
+    pub fn get_mut(&mut self, x: u32, y: u32) -> Option<&mut AsciiCell<C>> {
+        let index = self.index(x, y)?;
+        self.cells.get_mut(index)
+    }
+
+    /// Replaces the cell at (`x`, `y`) and returns the previous one.
+    ///
+    /// # Errors
+    /// If the coordinates are out of bounds `Err(cell)` is returned.
+    pub fn set(&mut self, x: u32, y: u32, cell: AsciiCell<C>) -> Result<AsciiCell<C>, AsciiCell<C>> {
+        match self.get_mut(x, y) {
+            Some(old) => Ok(std::mem::replace(old, cell)),
+            None => Err(cell),
+        }
+    }
+
+    /// The rows of the image, from top to bottom.
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[AsciiCell<C>]> + ExactSizeIterator {
+        // `chunks` panics on a chunk size of zero, but an image without width has no cells anyway.
+        self.cells.chunks(self.width.max(1) as usize)
+    }
+
+    /// The rows of the image, from top to bottom.
+    pub fn rows_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut [AsciiCell<C>]> + ExactSizeIterator {
+        self.cells.chunks_mut(self.width.max(1) as usize)
+    }
+
+    /// All the cells of the image along with their coordinates, in the same order as `cells`.
+    pub fn enumerate_cells(&self) -> impl Iterator<Item = (u32, u32, &AsciiCell<C>)> {
+        self.rows()
+            .zip(0..)
+            .flat_map(|(row, y)| row.iter().zip(0..).map(move |(cell, x)| (x, y, cell)))
+    }
+
+    /// All the cells of the image along with their coordinates, in the same order as `cells`.
+    pub fn enumerate_cells_mut(&mut self) -> impl Iterator<Item = (u32, u32, &mut AsciiCell<C>)> {
+        self.rows_mut()
+            .zip(0..)
+            .flat_map(|(row, y)| row.iter_mut().zip(0..).map(move |(cell, x)| (x, y, cell)))
+    }
+
+    /// Sets every cell in the rectangle with its top left corner at (`x`, `y`) to `cell`.
+    /// The parts of the rectangle that lie outside the image are ignored.
+    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, cell: AsciiCell<C>)
+    where
+        C: Clone,
+    {
+        let end_x = x.saturating_add(width).min(self.width);
+        let end_y = y.saturating_add(height).min(self.height());
+
+        if end_x <= x || end_y <= y {
+            return;
+        }
+
+        for row in self.rows_mut().skip(y as usize).take((end_y - y) as usize) {
+            row[x as usize..end_x as usize].fill(cell.clone());
+        }
+    }
 }
 
-// TODO: Add serial versions for non Send colors.
-impl<C: Color + Send> AsciiImage<C> {
+impl<C: Color + MaybeSend> AsciiImage<C>
+where
+    GradientMatcher: CellRenderer<C>,
+{
     /// Converts the image to ascii using the image's dimensions.
     /// However, the aspect ratio is kept by scaling one of the
     /// dimensions down using the font's aspect ratio.
-    pub fn from_image<G: AsRef<[char]> + Send + Sync>(
-        image: &DynamicImage,
+    pub fn from_image<I, G>(image: &I, font: &Font<G>) -> Self
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+    {
+        let (width, height) = natural_dimensions(image.dimensions(), font);
+        Self::from_image_with_dimensions(image, font, width, height)
+    }
+
+    /// Converts the image to ascii with the provided width,
+    /// choosing the height that keeps the aspect ratio.
+    /// An image without pixels is converted to an empty image.
+    pub fn from_image_with_width<I, G>(image: &I, font: &Font<G>, width: u32) -> Self
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+    {
+        let (width, height) = dimensions_with_width(image.dimensions(), font, width);
+        Self::from_image_with_dimensions(image, font, width, height)
+    }
+
+    /// Converts the image to ascii with the provided height,
+    /// choosing the width that keeps the aspect ratio.
+    /// An image without pixels is converted to an empty image.
+    pub fn from_image_with_height<I, G>(image: &I, font: &Font<G>, height: u32) -> Self
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+    {
+        let (width, height) = dimensions_with_height(image.dimensions(), font, height);
+        Self::from_image_with_dimensions(image, font, width, height)
+    }
+
+    pub fn from_image_with_dimensions<I, G>(
+        image: &I,
         font: &Font<G>,
-    ) -> Self {
-        let (font_width, font_height) = font.aspect_ratio().into_raw();
-
-        match font_width.cmp(&font_height) {
-            // font height > font width => downsample height
-            Ordering::Less => Self::from_image_with_width(image, font, image.width()),
-            // font height = font width => don't downsample
-            Ordering::Equal => Self::from_image_with_dimensions(image, font, image.width(), image.height()),
-            // font height < font width => downsample width
-            Ordering::Greater => Self::from_image_with_height(image, font, image.height()),
-        }
+        width: u32,
+        height: u32,
+    ) -> Self
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+    {
+        Self::from_image_with_conversion(image, font, &Conversion::default(), width, height)
     }
 
-    pub fn from_image_with_width<G: AsRef<[char]> + Send + Sync>(
-        image: &DynamicImage,
+    /// Converts the image to ascii so that it fits a box of `width` × `height` characters,
+    /// as described by `sizing`.
+    pub fn from_image_with_sizing<I, G>(
+        image: &I,
         font: &Font<G>,
+        conversion: &Conversion,
+        sizing: &Sizing<C>,
         width: u32,
-    ) -> Self {
-        let scaling_factor = Ratio::new(width, image.width());
-        let height = (scaling_factor * image.height() * font.aspect_ratio())
-            .round()
-            .to_integer();
-        Self::from_image_with_dimensions(image, font, width, height)
+        height: u32,
+    ) -> Self
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+    {
+        Self::from_image_with_sizing_and_renderer(
+            image,
+            font,
+            conversion,
+            &GradientMatcher,
+            sizing,
+            width,
+            height,
+        )
     }
 
-    pub fn from_image_with_height<G: AsRef<[char]> + Send + Sync>(
-        image: &DynamicImage,
+    /// Converts the image to ascii with the provided dimensions using the settings in `conversion`.
+    pub fn from_image_with_conversion<I, G>(
+        image: &I,
         font: &Font<G>,
+        conversion: &Conversion,
+        width: u32,
         height: u32,
-    ) -> Self {
-        let scaling_factor = Ratio::new(height, image.height());
-        let width = (scaling_factor * image.width() / font.aspect_ratio())
-            .round()
-            .to_integer();
-        Self::from_image_with_dimensions(image, font, width, height)
+    ) -> Self
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+    {
+        Self::from_image_with_renderer(image, font, conversion, &GradientMatcher, width, height)
     }
 
-    pub fn from_image_with_dimensions<G: AsRef<[char]> + Send + Sync>(
-        image: &DynamicImage,
+    /// Converts the rectangle of the image with its top left corner at (`x`, `y`)
+    /// to ascii with the provided dimensions, without copying it.
+    /// The parts of the rectangle that lie outside the image are ignored.
+    pub fn from_image_region<I, G>(
+        image: &I,
         font: &Font<G>,
+        conversion: &Conversion,
+        position: (u32, u32),
+        size: (u32, u32),
         width: u32,
         height: u32,
-    ) -> Self {
-        let area = width as u64 * height as u64;
+    ) -> Self
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+    {
+        Self::from_image_region_with_renderer(
+            image,
+            font,
+            conversion,
+            &GradientMatcher,
+            position,
+            size,
+            width,
+            height,
+        )
+    }
+}
+
+impl<C: Color + MaybeSend> AsciiImage<C> {
+    /// Converts the image to ascii with the provided dimensions using the settings in `conversion`,
+    /// drawing every cell using `renderer`.
+    ///
+    /// The cells are converted in parallel if the `rayon` feature is enabled.
+    pub fn from_image_with_renderer<I, G, R>(
+        image: &I,
+        font: &Font<G>,
+        conversion: &Conversion,
+        renderer: &R,
+        width: u32,
+        height: u32,
+    ) -> Self
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+        R: CellRenderer<C> + MaybeSync,
+    {
+        #[cfg(feature = "rayon")]
+        if conversion.preprocessing.is_none() {
+            Converter::new(image, font, conversion, renderer, width, height).parallel()
+        } else {
+            let image = conversion.preprocessing.apply(image);
+            Converter::new(&image, font, conversion, renderer, width, height).parallel()
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        Self::from_image_with_renderer_serial(image, font, conversion, renderer, width, height)
+    }
 
-        if area == 0 {
-            return AsciiImage::new()
+    /// Like `from_image_with_renderer`, but the cells are converted in `pool`.
+    #[cfg(feature = "rayon")]
+    pub fn from_image_with_renderer_in_pool<I, G, R>(
+        pool: &rayon::ThreadPool,
+        image: &I,
+        font: &Font<G>,
+        conversion: &Conversion,
+        renderer: &R,
+        width: u32,
+        height: u32,
+    ) -> Self
+    where
+        I: GenericImageView + Sync,
+        G: AsRef<[char]> + Send + Sync,
+        R: CellRenderer<C> + Sync,
+    {
+        pool.install(|| Self::from_image_with_renderer(image, font, conversion, renderer, width, height))
+    }
+
+    /// Like `from_image_with_sizing`, but every cell is drawn using `renderer`.
+    pub fn from_image_with_sizing_and_renderer<I, G, R>(
+        image: &I,
+        font: &Font<G>,
+        conversion: &Conversion,
+        renderer: &R,
+        sizing: &Sizing<C>,
+        width: u32,
+        height: u32,
+    ) -> Self
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+        R: CellRenderer<C> + MaybeSync,
+    {
+        let (scaled_width, scaled_height) =
+            scaled_dimensions(sizing, image.dimensions(), font.aspect_ratio(), (width, height));
+
+        let scaled =
+            Self::from_image_with_renderer(image, font, conversion, renderer, scaled_width, scaled_height);
+
+        match sizing {
+            Sizing::Fit | Sizing::Stretch => scaled,
+            Sizing::Fill(gravity) => {
+                let (x, y) = gravity.offset((width, height), (scaled_width, scaled_height));
+                scaled.crop(x, y, width, height)
+            }
+            Sizing::Letterbox { padding, gravity } => {
+                let (x, y) = gravity.offset((width, height), (scaled_width, scaled_height));
+                let mut boxed = AsciiImage::filled(width, height, *padding);
+                boxed.paste_at(&scaled, x as i64, y as i64);
+                boxed
+            }
         }
+    }
 
-        let cells = (0..area)
-            .into_par_iter()
-            .map(|index| {
-                let char_x = (index % width as u64) as u32;
-                let char_y = (index / width as u64) as u32;
+    /// Like `from_image_region`, but every cell is drawn using `renderer`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_image_region_with_renderer<I, G, R>(
+        image: &I,
+        font: &Font<G>,
+        conversion: &Conversion,
+        renderer: &R,
+        (x, y): (u32, u32),
+        (region_width, region_height): (u32, u32),
+        width: u32,
+        height: u32,
+    ) -> Self
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+        R: CellRenderer<C> + MaybeSync,
+    {
+        let (image_width, image_height) = image.dimensions();
+        let (x, y) = (x.min(image_width), y.min(image_height));
+        let region_width = region_width.min(image_width - x);
+        let region_height = region_height.min(image_height - y);
 
-                let x = char_x * image.width() / width;
-                let y = char_y * image.height() / height;
+        let region = image.view(x, y, region_width, region_height);
 
-                let width = (char_x + 1) * image.width() / width - x;
-                let height = (char_y + 1) * image.height() / height - y;
+        Self::from_image_with_renderer(&*region, font, conversion, renderer, width, height)
+    }
+}
 
-                let view = image.view(x, y, width.max(1), height.max(1));
+/// Serial versions of the conversions, which convert the cells one at a time on the current thread,
+/// even if the `rayon` feature is enabled.
+impl<C: Color> AsciiImage<C> {
+    /// Like `from_image`, but the cells are converted one at a time.
+    pub fn from_image_serial<I: GenericImageView, G: AsRef<[char]>>(image: &I, font: &Font<G>) -> Self
+    where
+        GradientMatcher: CellRenderer<C>,
+    {
+        let (width, height) = natural_dimensions(image.dimensions(), font);
+        Self::from_image_with_dimensions_serial(image, font, width, height)
+    }
 
-                C::new_cell(view, font)
-            })
-            .collect();
+    /// Like `from_image_with_width`, but the cells are converted one at a time.
+    pub fn from_image_with_width_serial<I: GenericImageView, G: AsRef<[char]>>(
+        image: &I,
+        font: &Font<G>,
+        width: u32,
+    ) -> Self
+    where
+        GradientMatcher: CellRenderer<C>,
+    {
+        let (width, height) = dimensions_with_width(image.dimensions(), font, width);
+        Self::from_image_with_dimensions_serial(image, font, width, height)
+    }
 
-        AsciiImage { width, cells }
+    /// Like `from_image_with_height`, but the cells are converted one at a time.
+    pub fn from_image_with_height_serial<I: GenericImageView, G: AsRef<[char]>>(
+        image: &I,
+        font: &Font<G>,
+        height: u32,
+    ) -> Self
+    where
+        GradientMatcher: CellRenderer<C>,
+    {
+        let (width, height) = dimensions_with_height(image.dimensions(), font, height);
+        Self::from_image_with_dimensions_serial(image, font, width, height)
+    }
+
+    /// Like `from_image_with_dimensions`, but the cells are converted one at a time.
+    pub fn from_image_with_dimensions_serial<I: GenericImageView, G: AsRef<[char]>>(
+        image: &I,
+        font: &Font<G>,
+        width: u32,
+        height: u32,
+    ) -> Self
+    where
+        GradientMatcher: CellRenderer<C>,
+    {
+        Self::from_image_with_conversion_serial(image, font, &Conversion::default(), width, height)
+    }
+
+    /// Like `from_image_with_conversion`, but the cells are converted one at a time.
+    pub fn from_image_with_conversion_serial<I: GenericImageView, G: AsRef<[char]>>(
+        image: &I,
+        font: &Font<G>,
+        conversion: &Conversion,
+        width: u32,
+        height: u32,
+    ) -> Self
+    where
+        GradientMatcher: CellRenderer<C>,
+    {
+        Self::from_image_with_renderer_serial(image, font, conversion, &GradientMatcher, width, height)
+    }
+
+    /// Like `from_image_with_renderer`, but the cells are converted one at a time.
+    pub fn from_image_with_renderer_serial<I, G, R>(
+        image: &I,
+        font: &Font<G>,
+        conversion: &Conversion,
+        renderer: &R,
+        width: u32,
+        height: u32,
+    ) -> Self
+    where
+        I: GenericImageView,
+        G: AsRef<[char]>,
+        R: CellRenderer<C>,
+    {
+        if conversion.preprocessing.is_none() {
+            Converter::new(image, font, conversion, renderer, width, height).serial()
+        } else {
+            let image = conversion.preprocessing.apply(image);
+            Converter::new(&image, font, conversion, renderer, width, height).serial()
+        }
+    }
+}
+
+/// The dimensions `from_image` converts an image of `image_width` × `image_height` pixels to.
+/// The aspect ratio is kept by scaling one of the dimensions down using the font's aspect ratio.
+fn natural_dimensions<G: AsRef<[char]>>(
+    (image_width, image_height): (u32, u32),
+    font: &Font<G>,
+) -> (u32, u32) {
+    let (font_width, font_height) = font.aspect_ratio().into_raw();
+
+    match font_width.cmp(&font_height) {
+        // font height > font width => downsample height
+        Ordering::Less => dimensions_with_width((image_width, image_height), font, image_width),
+        // font height = font width => don't downsample
+        Ordering::Equal => (image_width, image_height),
+        // font height < font width => downsample width
+        Ordering::Greater => dimensions_with_height((image_width, image_height), font, image_height),
     }
 }
 
+/// The dimensions that keep the aspect ratio of an image that is converted to `width` characters.
+/// An image without pixels has no cells.
+fn dimensions_with_width<G: AsRef<[char]>>(
+    (image_width, image_height): (u32, u32),
+    font: &Font<G>,
+    width: u32,
+) -> (u32, u32) {
+    if image_width == 0 || image_height == 0 {
+        return (0, 0);
+    }
+
+    let scaling_factor = Ratio::new(width, image_width);
+    let height = (scaling_factor * image_height * font.aspect_ratio())
+        .round()
+        .to_integer();
+    (width, height)
+}
+
+/// The dimensions that keep the aspect ratio of an image that is converted to `height` characters.
+/// An image without pixels has no cells.
+fn dimensions_with_height<G: AsRef<[char]>>(
+    (image_width, image_height): (u32, u32),
+    font: &Font<G>,
+    height: u32,
+) -> (u32, u32) {
+    if image_width == 0 || image_height == 0 {
+        return (0, 0);
+    }
+
+    let scaling_factor = Ratio::new(height, image_height);
+    let width = (scaling_factor * image_width / font.aspect_ratio())
+        .round()
+        .to_integer();
+    (width, height)
+}
+
 impl<C: Color + PartialEq> AsciiImage<C> {
     // TODO: Use `Formatter` and make public.
     pub(crate) fn fmt_line(&self, f: &mut String, y: u32) -> std::fmt::Result {
@@ -170,6 +572,7 @@ impl<C: Color + PartialEq> AsciiImage<C> {
 
             let previous = previous.get_or_insert(*cell);
             previous.background = cell.background;
+            previous.attributes = cell.attributes;
             previous.foreground = cell.foreground.or(previous.foreground);
         }
 
@@ -0,0 +1,128 @@
+use crate::cell::AsciiCell;
+use crate::image::AsciiImage;
+
+impl<C: Clone> AsciiImage<C> {
+    /// Copies the rectangle with its top left corner at (`x`, `y`) into a new image.
+    /// The rectangle is clipped to the bounds of the image.
+    #[must_use]
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        let end_x = x.saturating_add(width).min(self.width);
+        let end_y = y.saturating_add(height).min(self.height());
+
+        if end_x <= x || end_y <= y {
+            return AsciiImage::new();
+        }
+
+        let cells = self
+            .rows()
+            .skip(y as usize)
+            .take((end_y - y) as usize)
+            .flat_map(|row| &row[x as usize..end_x as usize])
+            .cloned()
+            .collect();
+
+        AsciiImage {
+            width: end_x - x,
+            cells,
+        }
+    }
+
+    /// Copies `other` onto `self` with its top left corner at (`x`, `y`).
+    /// The parts of `other` that lie outside `self` are ignored.
+    pub fn paste_at(&mut self, other: &Self, x: i64, y: i64) {
+        self.zip_region(other, x, y, |lower, upper| *lower = upper.clone());
+    }
+
+    /// Draws `other` on top of `self` with its top left corner at (`x`, `y`).
+    /// The parts of `other` that lie outside `self` are ignored.
+    ///
+    /// Cells in `other` without a foreground are see-through, whatever their background.
+    /// Cells whose background `is_transparent` draw their foreground over the background of the cell below.
+    /// Other cells replace the cell below entirely.
+    pub fn overlay(&mut self, other: &Self, x: i64, y: i64, is_transparent: impl Fn(&C) -> bool) {
+        self.zip_region(other, x, y, |lower, upper| {
+            let Some(foreground) = &upper.foreground else {
+                return;
+            };
+
+            if is_transparent(&upper.background) {
+                lower.foreground = Some(foreground.clone());
+                lower.attributes = upper.attributes;
+            } else {
+                *lower = upper.clone();
+            }
+        });
+    }
+
+    /// Places the images next to each other, from left to right.
+    /// Images shorter than the tallest one are padded below with `padding`.
+    #[must_use]
+    pub fn concat_horizontal<'a>(images: impl IntoIterator<Item = &'a Self>, padding: AsciiCell<C>) -> Self
+    where
+        C: 'a,
+    {
+        let images: Vec<_> = images.into_iter().collect();
+
+        let width = images.iter().fold(0, |width: u32, image| width.saturating_add(image.width));
+        let height = images.iter().map(|image| image.height()).max().unwrap_or(0);
+
+        let mut result = AsciiImage::filled(width, height, padding);
+
+        let mut x = 0;
+        for image in images {
+            result.paste_at(image, x, 0);
+            x += image.width as i64;
+        }
+
+        result
+    }
+
+    /// Places the images on top of each other, from top to bottom.
+    /// Images narrower than the widest one are padded to the right with `padding`.
+    #[must_use]
+    pub fn concat_vertical<'a>(images: impl IntoIterator<Item = &'a Self>, padding: AsciiCell<C>) -> Self
+    where
+        C: 'a,
+    {
+        let images: Vec<_> = images.into_iter().collect();
+
+        let width = images.iter().map(|image| image.width).max().unwrap_or(0);
+        let height = images.iter().fold(0, |height: u32, image| height.saturating_add(image.height()));
+
+        let mut result = AsciiImage::filled(width, height, padding);
+
+        let mut y = 0;
+        for image in images {
+            result.paste_at(image, 0, y);
+            y += image.height() as i64;
+        }
+
+        result
+    }
+
+    /// Calls `f` with every pair of overlapping cells
+    /// when `other` is placed with its top left corner at (`x`, `y`).
+    fn zip_region(
+        &mut self,
+        other: &Self,
+        x: i64,
+        y: i64,
+        mut f: impl FnMut(&mut AsciiCell<C>, &AsciiCell<C>),
+    ) {
+        for (row, other_y) in other.rows().zip(0..) {
+            let Ok(self_y) = u32::try_from(y + other_y) else {
+                continue;
+            };
+
+            for (upper, other_x) in row.iter().zip(0..) {
+                let Ok(self_x) = u32::try_from(x + other_x) else {
+                    continue;
+                };
+
+                if let Some(lower) = self.get_mut(self_x, self_y) {
+                    f(lower, upper);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::cell::{AsciiCell, Attributes, Foreground};
+use crate::color::Color;
+use crate::conversion::Conversion;
+use crate::font::Font;
+use crate::image::AsciiImage;
+use crate::line_art::Gradients;
+use crate::renderer::CellRenderer;
+use image::GenericImageView;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+/// Converts a preprocessed image to ascii, one cell at a time.
+pub(super) struct Converter<'a, I, G, R> {
+    image: &'a I,
+    font: &'a Font<G>,
+    conversion: &'a Conversion,
+    renderer: &'a R,
+    edges: Option<Gradients>,
+    width: u32,
+    height: u32,
+}
+
+impl<'a, I: GenericImageView, G: AsRef<[char]>, R> Converter<'a, I, G, R> {
+    pub(super) fn new(
+        image: &'a I,
+        font: &'a Font<G>,
+        conversion: &'a Conversion,
+        renderer: &'a R,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let area = width as u64 * height as u64;
+
+        // The edges aren't needed if there are no cells.
+        let edges = conversion
+            .line_art
+            .filter(|_| area != 0)
+            .map(|detector| detector.detect(image));
+
+        Converter {
+            image,
+            font,
+            conversion,
+            renderer,
+            edges,
+            width,
+            height,
+        }
+    }
+
+    fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// Converts all cells on the current thread.
+    pub(super) fn serial<C: Color>(&self) -> AsciiImage<C>
+    where
+        R: CellRenderer<C>,
+    {
+        AsciiImage {
+            width: self.width,
+            cells: (0..self.area()).map(|index| self.cell(index)).collect(),
+        }
+    }
+
+    /// Converts the cells in parallel.
+    #[cfg(feature = "rayon")]
+    pub(super) fn parallel<C: Color + Send>(&self) -> AsciiImage<C>
+    where
+        I: Sync,
+        G: Sync,
+        R: CellRenderer<C> + Sync,
+    {
+        AsciiImage {
+            width: self.width,
+            cells: (0..self.area()).into_par_iter().map(|index| self.cell(index)).collect(),
+        }
+    }
+
+    /// Converts the cell at `index`, going from left to right, wrapping from top to bottom.
+    fn cell<C: Color>(&self, index: u64) -> AsciiCell<C>
+    where
+        R: CellRenderer<C>,
+    {
+        let Converter {
+            image,
+            font,
+            conversion,
+            renderer,
+            width,
+            height,
+            ..
+        } = *self;
+
+        let char_x = (index % width as u64) as u32;
+        let char_y = (index / width as u64) as u32;
+
+        // The exact bounds of the cell, without rounding to whole pixels.
+        let scale_x = image.width() as f64 / width as f64;
+        let scale_y = image.height() as f64 / height as f64;
+        let top_left = (char_x as f64 * scale_x, char_y as f64 * scale_y);
+        let bottom_right = ((char_x + 1) as f64 * scale_x, (char_y + 1) as f64 * scale_y);
+
+        let x = char_x * image.width() / width;
+        let y = char_y * image.height() / height;
+
+        let width = (char_x + 1) * image.width() / width - x;
+        let height = (char_y + 1) * image.height() / height - y;
+
+        let width = width.max(1);
+        let height = height.max(1);
+
+        let cell = match conversion.filter.sample(image, top_left, bottom_right, conversion.linear_light) {
+            Some(color) => renderer.render_color(color, font, conversion),
+            None => renderer.render_cell(&*image.view(x, y, width, height), font, conversion),
+        };
+
+        let line = self
+            .edges
+            .as_ref()
+            .and_then(|edges| edges.line(image, &conversion.line_characters, x, y, width, height));
+
+        match line {
+            Some((character, color)) => AsciiCell {
+                background: cell.background,
+                foreground: Some(Foreground {
+                    color: C::from_rgb(color),
+                    character,
+                }),
+                attributes: Attributes::NONE,
+            },
+            None => cell,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::cell::AsciiCell;
+use crate::color::Color;
+use crate::image::AsciiImage;
+use image::Rgb;
+use std::fmt::Write;
+
+impl<C: Color> AsciiImage<C> {
+    /// Formats the image as HTML `<span>` elements with inline styles,
+    /// with the rows separated by newlines.
+    /// Adjacent cells with the same style share a span.
+    ///
+    /// The result is meant to be placed in a `<pre>` element or another element
+    /// which preserves whitespace and uses a monospace font.
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+
+        for (y, row) in self.rows().enumerate() {
+            if y != 0 {
+                html.push('\n');
+            }
+
+            let mut previous_style = None;
+
+            for cell in row {
+                let style = style(cell);
+
+                if previous_style.as_ref() != Some(&style) {
+                    if previous_style.is_some() {
+                        html.push_str("</span>");
+                    }
+                    // Writing to a `String` never fails.
+                    let _ = write!(html, "<span style=\"{style}\">");
+                    previous_style = Some(style);
+                }
+
+                let character = cell.foreground.map_or(' ', |foreground| foreground.character);
+                match character {
+                    '<' => html.push_str("&lt;"),
+                    '>' => html.push_str("&gt;"),
+                    '&' => html.push_str("&amp;"),
+                    '"' => html.push_str("&quot;"),
+                    character => html.push(character),
+                }
+            }
+
+            if previous_style.is_some() {
+                html.push_str("</span>");
+            }
+        }
+
+        html
+    }
+}
+
+/// The inline CSS which draws the cell.
+fn style<C: Color>(cell: &AsciiCell<C>) -> String {
+    let background = cell.background.to_rgb();
+    let foreground = cell
+        .foreground
+        .map_or(background, |foreground| foreground.color.to_rgb_with_attributes(cell.attributes));
+
+    let (foreground, background) = if cell.attributes.reverse {
+        (background, foreground)
+    } else {
+        (foreground, background)
+    };
+
+    let hex = |Rgb([r, g, b]): Rgb<u8>| format!("#{r:02x}{g:02x}{b:02x}");
+
+    let mut style = format!("color:{};background-color:{}", hex(foreground), hex(background));
+
+    if cell.attributes.bold {
+        style.push_str(";font-weight:bold");
+    }
+    if cell.attributes.italic {
+        style.push_str(";font-style:italic");
+    }
+    if cell.attributes.underline {
+        style.push_str(";text-decoration:underline");
+    }
+
+    style
+}
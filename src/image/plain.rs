@@ -0,0 +1,30 @@
+use crate::color::colorless::shade;
+use crate::color::Color;
+use crate::font::Font;
+use crate::image::AsciiImage;
+
+impl<C: Color> AsciiImage<C> {
+    /// Formats the image as text without any escape codes, with the rows separated by newlines.
+    ///
+    /// Cells keep their characters, while cells without one,
+    /// which would otherwise be blank, get a character from the gradient of `font`
+    /// based on the luminance of their background, like when converting to `Colorless`.
+    /// Reversed spaces are filled with their foreground color, so they are shaded based on that instead.
+    /// This assumes light text on a dark background; for dark text on a light one, reverse the gradient.
+    #[must_use]
+    pub fn to_plain_text<G: AsRef<[char]>>(&self, font: &Font<G>) -> String {
+        let rows = self.rows().map(|row| {
+            row.iter()
+                .map(|cell| match cell.foreground {
+                    Some(foreground) if foreground.character == ' ' && cell.attributes.reverse => {
+                        shade(foreground.color.to_rgb_with_attributes(cell.attributes), font)
+                    }
+                    Some(foreground) => foreground.character,
+                    None => shade(cell.background.to_rgb(), font),
+                })
+                .collect::<String>()
+        });
+
+        rows.collect::<Vec<_>>().join("\n")
+    }
+}
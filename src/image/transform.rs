@@ -0,0 +1,198 @@
+use crate::font::Font;
+use crate::image::AsciiImage;
+use num_rational::Ratio;
+
+/// The glyph that looks like `character` mirrored along a vertical axis.
+fn mirror_horizontally(character: char) -> char {
+    match character {
+        '/' => '\\',
+        '\\' => '/',
+        '(' => ')',
+        ')' => '(',
+        '<' => '>',
+        '>' => '<',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '▌' => '▐',
+        '▐' => '▌',
+        '▘' => '▝',
+        '▝' => '▘',
+        '▖' => '▗',
+        '▗' => '▖',
+        character => character,
+    }
+}
+
+/// The glyph that looks like `character` mirrored along a horizontal axis.
+fn mirror_vertically(character: char) -> char {
+    match character {
+        '/' => '\\',
+        '\\' => '/',
+        '▀' => '▄',
+        '▄' => '▀',
+        '▘' => '▖',
+        '▖' => '▘',
+        '▝' => '▗',
+        '▗' => '▝',
+        '^' => 'v',
+        'v' => '^',
+        character => character,
+    }
+}
+
+/// The glyph that looks like `character` rotated a quarter turn clockwise.
+fn rotate_clockwise(character: char) -> char {
+    match character {
+        '-' => '|',
+        '|' => '-',
+        '/' => '\\',
+        '\\' => '/',
+        '▀' => '▐',
+        '▐' => '▄',
+        '▄' => '▌',
+        '▌' => '▀',
+        '▘' => '▝',
+        '▝' => '▗',
+        '▗' => '▖',
+        '▖' => '▘',
+        character => character,
+    }
+}
+
+impl<C> AsciiImage<C> {
+    /// Mirrors the image along a vertical axis.
+    /// If `mirror_glyphs` is set, directional characters such as `/` and `(` are swapped
+    /// with their mirrored counterparts so that the art still looks right.
+    pub fn flip_horizontal(&mut self, mirror_glyphs: bool) {
+        for row in self.rows_mut() {
+            row.reverse();
+        }
+
+        if mirror_glyphs {
+            self.map_characters(mirror_horizontally);
+        }
+    }
+
+    /// Mirrors the image along a horizontal axis.
+    /// If `mirror_glyphs` is set, directional characters such as `/` and `▀` are swapped
+    /// with their mirrored counterparts so that the art still looks right.
+    pub fn flip_vertical(&mut self, mirror_glyphs: bool) {
+        if self.cells.is_empty() {
+            return;
+        }
+
+        let width = self.width as usize;
+        let height = self.height() as usize;
+
+        for y in 0..height / 2 {
+            let (top, bottom) = self.cells.split_at_mut((height - 1 - y) * width);
+            top[y * width..(y + 1) * width].swap_with_slice(&mut bottom[..width]);
+        }
+
+        if mirror_glyphs {
+            self.map_characters(mirror_vertically);
+        }
+    }
+
+    /// Rotates the image half a turn.
+    /// See [`flip_horizontal`](Self::flip_horizontal) for the meaning of `mirror_glyphs`.
+    pub fn rotate_180(&mut self, mirror_glyphs: bool) {
+        self.cells.reverse();
+
+        if mirror_glyphs {
+            self.map_characters(|character| mirror_vertically(mirror_horizontally(character)));
+        }
+    }
+
+    fn map_characters(&mut self, f: impl Fn(char) -> char) {
+        for foreground in self.cells.iter_mut().filter_map(|cell| cell.foreground.as_mut()) {
+            foreground.character = f(foreground.character);
+        }
+    }
+}
+
+impl<C: Clone> AsciiImage<C> {
+    /// Swaps the rows and columns of the image.
+    /// Note that this does not take the aspect ratio of the font into account,
+    /// see [`rotate_90`](Self::rotate_90).
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        if self.cells.is_empty() {
+            return AsciiImage::new();
+        }
+
+        let width = self.width as usize;
+        let height = self.height() as usize;
+
+        let cells = (0..width * height)
+            .map(|index| self.cells[(index % height) * width + index / height].clone())
+            .collect();
+
+        AsciiImage {
+            width: height as u32,
+            cells,
+        }
+    }
+
+    /// Resizes the image to the provided dimensions using nearest-neighbour sampling.
+    #[must_use]
+    pub fn resize(&self, width: u32, height: u32) -> Self {
+        let area = width as u64 * height as u64;
+
+        if area == 0 || self.cells.is_empty() {
+            return AsciiImage::new();
+        }
+
+        // Sample the cell under the center of each new cell.
+        let sample = |index: u64, old: u32, new: u32| ((2 * index + 1) * old as u64 / (2 * new as u64)) as u32;
+
+        let cells = (0..area)
+            .map(|index| {
+                let x = sample(index % width as u64, self.width, width);
+                let y = sample(index / width as u64, self.height(), height);
+
+                // The sampled coordinates are always in bounds.
+                self.get(x, y).unwrap().clone()
+            })
+            .collect();
+
+        AsciiImage { width, cells }
+    }
+
+    /// Rotates the image a quarter turn clockwise.
+    ///
+    /// Since characters aren't square, the image is resampled using the font's aspect ratio
+    /// so that the rotated image keeps its proportions.
+    /// If `mirror_glyphs` is set, directional characters such as `|` and `▀` are swapped
+    /// with their rotated counterparts so that the art still looks right.
+    #[must_use]
+    pub fn rotate_90<G: AsRef<[char]>>(&self, font: &Font<G>, mirror_glyphs: bool) -> Self {
+        if self.cells.is_empty() {
+            return AsciiImage::new();
+        }
+
+        let mut rotated = self.transpose();
+        rotated.flip_horizontal(false);
+
+        if mirror_glyphs {
+            rotated.map_characters(rotate_clockwise);
+        }
+
+        // The old columns become rows, which are `1 / aspect_ratio` times as tall as they are wide.
+        let width = (Ratio::from(self.height()) / font.aspect_ratio()).round().to_integer();
+        let height = (Ratio::from(self.width) * font.aspect_ratio()).round().to_integer();
+
+        rotated.resize(width, height)
+    }
+
+    /// Rotates the image a quarter turn counterclockwise.
+    /// See [`rotate_90`](Self::rotate_90).
+    #[must_use]
+    pub fn rotate_270<G: AsRef<[char]>>(&self, font: &Font<G>, mirror_glyphs: bool) -> Self {
+        let mut rotated = self.rotate_90(font, mirror_glyphs);
+        rotated.rotate_180(mirror_glyphs);
+        rotated
+    }
+}
@@ -0,0 +1,173 @@
+//! Output for IRC, using the formatting codes of mIRC, which most clients understand.
+//!
+//! Any color type can be output, the colors are approximated using [`Mirc`] colors.
+//! Converting to `Mirc` directly gives the best results.
+
+use crate::cell::{AsciiCell, Attributes};
+use crate::color::mirc::Mirc;
+use crate::color::Color;
+use crate::image::AsciiImage;
+use image::Rgb;
+use std::fmt::Write;
+
+const COLOR: char = '\x03';
+const BOLD: char = '\x02';
+const ITALIC: char = '\x1D';
+const UNDERLINE: char = '\x1F';
+
+/// IRC messages are limited to 512 bytes, including the command, the channel
+/// and the prefix the server adds when relaying the message.
+/// This leaves some room for them.
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 400;
+
+/// How to format an image for IRC.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct IrcOptions {
+    /// The maximum length of a message in bytes.
+    /// Rows that don't fit are continued in the next message, which breaks up the image,
+    /// so the image should be narrow enough for its rows to fit.
+    pub max_line_length: usize,
+    /// Whether the extended colors 16 to 98 may be used.
+    /// Otherwise only the classic colors, which all clients support, are used.
+    pub extended_colors: bool,
+}
+
+impl Default for IrcOptions {
+    fn default() -> Self {
+        IrcOptions {
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+            extended_colors: true,
+        }
+    }
+}
+
+/// A cell as drawn by an IRC client.
+#[derive(Copy, Clone)]
+struct IrcCell {
+    character: char,
+    foreground: Mirc,
+    background: Mirc,
+    attributes: Attributes,
+}
+
+impl IrcCell {
+    fn new<C: Color>(cell: &AsciiCell<C>, options: &IrcOptions) -> IrcCell {
+        let to_mirc = |color: Rgb<u8>| {
+            if options.extended_colors {
+                Mirc::from_rgb(color)
+            } else {
+                Mirc::classic_from_rgb(color)
+            }
+        };
+
+        let background = to_mirc(cell.background.to_rgb());
+        let (character, foreground) = match cell.foreground {
+            Some(foreground) => (
+                foreground.character,
+                to_mirc(foreground.color.to_rgb_with_attributes(cell.attributes)),
+            ),
+            None => (' ', background),
+        };
+
+        // Reverse video isn't supported by all clients, so the colors are swapped here instead.
+        let (foreground, background) = if cell.attributes.reverse {
+            (background, foreground)
+        } else {
+            (foreground, background)
+        };
+
+        IrcCell {
+            character,
+            foreground,
+            background,
+            attributes: cell.attributes,
+        }
+    }
+}
+
+/// The formatting in effect at some point in a message.
+#[derive(Copy, Clone, Default)]
+struct Formatting {
+    colors: Option<(Mirc, Mirc)>,
+    attributes: Attributes,
+}
+
+impl Formatting {
+    /// Writes the cell along with the codes needed to format it, updating the formatting.
+    fn write(&mut self, cell: IrcCell, to: &mut String) {
+        let toggles = [
+            (self.attributes.bold != cell.attributes.bold, BOLD),
+            (self.attributes.italic != cell.attributes.italic, ITALIC),
+            (self.attributes.underline != cell.attributes.underline, UNDERLINE),
+        ];
+        for (toggled, code) in toggles {
+            if toggled {
+                to.push(code);
+            }
+        }
+        self.attributes = cell.attributes;
+
+        let is_blank = cell.character == ' ';
+
+        match self.colors {
+            // A space only shows the background, so the foreground can stay.
+            Some((_, background)) if is_blank && background == cell.background => {}
+            Some((foreground, background)) if foreground == cell.foreground && background == cell.background => {}
+            // Leaving out the background keeps it,
+            // unless the character is a comma, which would be read as the start of a background.
+            Some((_, background)) if background == cell.background && cell.character != ',' => {
+                write!(to, "{COLOR}{:02}", cell.foreground.code()).unwrap_or_default();
+                self.colors = Some((cell.foreground, background));
+            }
+            Some((foreground, _)) if is_blank => {
+                write!(to, "{COLOR}{:02},{:02}", foreground.code(), cell.background.code()).unwrap_or_default();
+                self.colors = Some((foreground, cell.background));
+            }
+            _ => {
+                write!(to, "{COLOR}{:02},{:02}", cell.foreground.code(), cell.background.code()).unwrap_or_default();
+                self.colors = Some((cell.foreground, cell.background));
+            }
+        }
+
+        to.push(cell.character);
+    }
+}
+
+impl<C: Color> AsciiImage<C> {
+    /// Formats the image as IRC messages, at least one per row.
+    /// Every message contains at least one cell, even if it doesn't fit.
+    #[must_use]
+    pub fn to_irc(&self, options: &IrcOptions) -> Vec<String> {
+        let mut messages = Vec::with_capacity(self.height() as usize);
+
+        for row in self.rows() {
+            let mut message = String::new();
+            let mut formatting = Formatting::default();
+
+            for cell in row {
+                let cell = IrcCell::new(cell, options);
+
+                let mut piece = String::new();
+                let mut continued = formatting;
+                continued.write(cell, &mut piece);
+
+                if !message.is_empty() && options.max_line_length < message.len() + piece.len() {
+                    // Clients reset the formatting at the start of every message.
+                    messages.push(std::mem::take(&mut message));
+                    formatting = Formatting::default();
+
+                    piece.clear();
+                    continued = formatting;
+                    continued.write(cell, &mut piece);
+                }
+
+                message.push_str(&piece);
+                formatting = continued;
+            }
+
+            messages.push(message);
+        }
+
+        messages
+    }
+}
@@ -1,9 +1,30 @@
 #![deny(clippy::all)]
 
+pub mod ansi_art;
 pub mod cell;
 pub mod color;
+pub mod conversion;
+pub mod cp437;
+pub mod figlet;
+pub mod file;
+pub mod filter;
 pub mod font;
 pub mod image;
+pub mod irc;
+pub mod line_art;
+pub mod packed;
+pub mod parallel;
+pub mod preprocessing;
+pub mod renderer;
+#[cfg(feature = "serde")]
+mod serialization;
 mod sgr;
+pub mod sizing;
+#[cfg(feature = "terminal")]
+pub mod terminal;
+#[cfg(feature = "ratatui")]
+pub mod tui;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,287 @@
+use crate::color::util::{normalize, to_rgba8};
+use image::imageops::blur;
+use image::{GenericImageView, ImageBuffer, Luma, Pixel, Rgb};
+
+/// The standard deviation of the blur applied before Canny edge detection.
+const CANNY_BLUR: f32 = 1.0;
+
+/// Finds the edges in an image, which are then drawn using directional characters.
+///
+/// All thresholds are gradient magnitudes, where `1` is the magnitude
+/// of a sharp edge between black and white.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum EdgeDetector {
+    /// Every pixel with a Sobel gradient magnitude of at least `threshold` is an edge.
+    Sobel { threshold: f32 },
+    /// The Canny edge detector: the image is blurred, the Sobel edges are thinned,
+    /// and pixels with a magnitude of at least `high` are edges,
+    /// along with pixels with a magnitude of at least `low` that are connected to them.
+    Canny { low: f32, high: f32 },
+}
+
+impl Default for EdgeDetector {
+    fn default() -> Self {
+        EdgeDetector::Sobel { threshold: 0.5 }
+    }
+}
+
+/// The characters lines are drawn with.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct LineCharacters {
+    pub vertical: char,
+    pub horizontal: char,
+    /// A horizontal line along the bottom of the cell.
+    pub low_horizontal: char,
+    /// A line from the bottom left to the top right.
+    pub rising: char,
+    /// A line from the top left to the bottom right.
+    pub falling: char,
+}
+
+impl LineCharacters {
+    /// `|`, `-`, `_`, `/` and `\`.
+    pub const ASCII: LineCharacters = LineCharacters {
+        vertical: '|',
+        horizontal: '-',
+        low_horizontal: '_',
+        rising: '/',
+        falling: '\\',
+    };
+
+    /// Box drawing lines, which connect to the neighbouring cells and are part of code page 437.
+    /// There are no diagonal box drawing characters, so those stay ascii.
+    pub const BOX_DRAWING: LineCharacters = LineCharacters {
+        vertical: '│',
+        horizontal: '─',
+        ..LineCharacters::ASCII
+    };
+}
+
+impl Default for LineCharacters {
+    fn default() -> Self {
+        LineCharacters::ASCII
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+struct Gradient {
+    x: f32,
+    y: f32,
+    is_edge: bool,
+}
+
+impl Gradient {
+    fn magnitude(&self) -> f32 {
+        self.x.hypot(self.y)
+    }
+}
+
+/// The gradient of every pixel in an image.
+pub(crate) struct Gradients {
+    width: u32,
+    gradients: Vec<Gradient>,
+}
+
+impl EdgeDetector {
+    pub(crate) fn detect(&self, image: &impl GenericImageView) -> Gradients {
+        let luma = ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+            let Luma([luma]) = image.get_pixel(x, y).to_luma();
+            Luma([normalize(luma)])
+        });
+
+        match *self {
+            EdgeDetector::Sobel { threshold } => {
+                let mut gradients = sobel(&luma);
+
+                for gradient in &mut gradients.gradients {
+                    gradient.is_edge = threshold <= gradient.magnitude();
+                }
+
+                gradients
+            }
+            EdgeDetector::Canny { low, high } => {
+                let mut gradients = sobel(&blur(&luma, CANNY_BLUR));
+                let magnitudes = gradients.thinned_magnitudes();
+                gradients.hysteresis(&magnitudes, low, high);
+                gradients
+            }
+        }
+    }
+}
+
+/// Computes the Sobel gradients of an image, clamping at the borders.
+/// The gradients are scaled so that a sharp edge between black and white has a magnitude of `1`.
+fn sobel(luma: &ImageBuffer<Luma<f32>, Vec<f32>>) -> Gradients {
+    let (width, height) = luma.dimensions();
+
+    let get = |x: i64, y: i64| {
+        let x = x.clamp(0, width as i64 - 1) as u32;
+        let y = y.clamp(0, height as i64 - 1) as u32;
+        luma.get_pixel(x, y).0[0]
+    };
+
+    let mut gradients = Vec::with_capacity(width as usize * height as usize);
+
+    for y in 0..height as i64 {
+        for x in 0..width as i64 {
+            let gradient_x = (get(x + 1, y - 1) + 2.0 * get(x + 1, y) + get(x + 1, y + 1))
+                - (get(x - 1, y - 1) + 2.0 * get(x - 1, y) + get(x - 1, y + 1));
+            let gradient_y = (get(x - 1, y + 1) + 2.0 * get(x, y + 1) + get(x + 1, y + 1))
+                - (get(x - 1, y - 1) + 2.0 * get(x, y - 1) + get(x + 1, y - 1));
+
+            gradients.push(Gradient {
+                x: gradient_x / 4.0,
+                y: gradient_y / 4.0,
+                is_edge: false,
+            });
+        }
+    }
+
+    Gradients { width, gradients }
+}
+
+impl Gradients {
+    fn height(&self) -> u32 {
+        (self.gradients.len() / self.width.max(1) as usize) as u32
+    }
+
+    fn get(&self, x: i64, y: i64) -> Option<&Gradient> {
+        if x < 0 || y < 0 || self.width as i64 <= x || self.height() as i64 <= y {
+            return None;
+        }
+
+        self.gradients.get(y as usize * self.width as usize + x as usize)
+    }
+
+    /// The magnitudes after non-maximum suppression:
+    /// pixels that aren't the strongest along their gradient get a magnitude of zero.
+    fn thinned_magnitudes(&self) -> Vec<f32> {
+        (0..self.gradients.len())
+            .map(|index| {
+                let x = (index % self.width as usize) as i64;
+                let y = (index / self.width as usize) as i64;
+
+                let gradient = self.gradients[index];
+                let magnitude = gradient.magnitude();
+
+                // Round the gradient direction to one of the four neighbouring axes.
+                let angle = gradient.y.atan2(gradient.x).to_degrees().rem_euclid(180.0);
+                let (dx, dy) = match angle {
+                    angle if !(22.5..157.5).contains(&angle) => (1, 0),
+                    angle if angle < 67.5 => (1, 1),
+                    angle if angle < 112.5 => (0, 1),
+                    _ => (-1, 1),
+                };
+
+                let neighbour = |x, y| self.get(x, y).map_or(0.0, Gradient::magnitude);
+
+                if neighbour(x + dx, y + dy) <= magnitude && neighbour(x - dx, y - dy) <= magnitude {
+                    magnitude
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+
+    /// Marks the strong pixels, and the weak pixels connected to them, as edges.
+    fn hysteresis(&mut self, magnitudes: &[f32], low: f32, high: f32) {
+        let mut stack: Vec<_> = (0..magnitudes.len())
+            .filter(|&index| high <= magnitudes[index])
+            .collect();
+
+        for &index in &stack {
+            self.gradients[index].is_edge = true;
+        }
+
+        while let Some(index) = stack.pop() {
+            let x = (index % self.width as usize) as i64;
+            let y = (index / self.width as usize) as i64;
+
+            for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+                if self.get(x + dx, y + dy).is_none() {
+                    continue;
+                }
+
+                let neighbour = (y + dy) as usize * self.width as usize + (x + dx) as usize;
+
+                if !self.gradients[neighbour].is_edge && low <= magnitudes[neighbour] {
+                    self.gradients[neighbour].is_edge = true;
+                    stack.push(neighbour);
+                }
+            }
+        }
+    }
+
+    /// Picks a directional character for the edges in the rectangle, along with their average color.
+    /// Returns `None` if the rectangle doesn't contain enough edge pixels to be drawn as a line.
+    pub(crate) fn line(
+        &self,
+        image: &impl GenericImageView,
+        characters: &LineCharacters,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Option<(char, Rgb<u8>)> {
+        let mut count = 0_u32;
+        // The structure tensor, with the gradients scaled to make the cell square.
+        let mut xx = 0.0;
+        let mut yy = 0.0;
+        let mut xy = 0.0;
+        let mut centroid_y = 0.0;
+        let mut color = [0_u64; 3];
+
+        for pixel_y in y..y + height {
+            for pixel_x in x..x + width {
+                let Some(gradient) = self.get(pixel_x as i64, pixel_y as i64) else {
+                    continue;
+                };
+
+                if !gradient.is_edge {
+                    continue;
+                }
+
+                let gradient_x = gradient.x * width as f32;
+                let gradient_y = gradient.y * height as f32;
+
+                count += 1;
+                xx += gradient_x * gradient_x;
+                yy += gradient_y * gradient_y;
+                xy += gradient_x * gradient_y;
+                centroid_y += (pixel_y - y) as f32 + 0.5;
+
+                let Rgb(rgb) = to_rgba8(image.get_pixel(pixel_x, pixel_y)).to_rgb();
+                for (sum, channel) in color.iter_mut().zip(rgb) {
+                    *sum += channel as u64;
+                }
+            }
+        }
+
+        // A line through the cell covers at least this many pixels.
+        if count == 0 || count < width.min(height) {
+            return None;
+        }
+
+        // The dominant direction of the gradient, on the interval (-90; 90] degrees.
+        let angle = (2.0 * xy).atan2(xx - yy).to_degrees() / 2.0;
+
+        // Lines are perpendicular to the gradient.
+        let character = match angle {
+            angle if angle.abs() < 22.5 => characters.vertical,
+            angle if 67.5 < angle.abs() => {
+                if 2.0 / 3.0 * height as f32 <= centroid_y / count as f32 {
+                    characters.low_horizontal
+                } else {
+                    characters.horizontal
+                }
+            }
+            angle if 0.0 < angle => characters.rising,
+            _ => characters.falling,
+        };
+
+        let color = color.map(|sum| (sum / count as u64) as u8);
+
+        Some((character, Rgb(color)))
+    }
+}
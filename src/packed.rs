@@ -0,0 +1,151 @@
+use crate::cell::{AsciiCell, Attributes, Foreground};
+use crate::color::compact::CompactColor;
+use crate::image::AsciiImage;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+
+/// Set in `PackedCell::flags` if the cell has a foreground.
+const HAS_FOREGROUND: u8 = 1 << 7;
+
+/// An `AsciiCell` stored using the compact representation of its colors,
+/// with the attributes and the presence of a foreground packed into a single byte.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PackedCell<Compact> {
+    character: char,
+    background: Compact,
+    foreground: Compact,
+    /// The bits of the attributes along with `HAS_FOREGROUND`.
+    flags: u8,
+}
+
+impl<Compact: Copy> PackedCell<Compact> {
+    #[must_use]
+    pub fn pack<C: CompactColor<Compact = Compact>>(cell: &AsciiCell<C>) -> Self {
+        let background = cell.background.compact();
+
+        match cell.foreground {
+            Some(foreground) => PackedCell {
+                character: foreground.character,
+                background,
+                foreground: foreground.color.compact(),
+                flags: cell.attributes.bits() | HAS_FOREGROUND,
+            },
+            None => PackedCell {
+                character: ' ',
+                background,
+                foreground: background,
+                flags: cell.attributes.bits(),
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn unpack<C: CompactColor<Compact = Compact>>(&self) -> AsciiCell<C> {
+        AsciiCell {
+            background: C::expand(self.background),
+            foreground: (self.flags & HAS_FOREGROUND != 0).then(|| Foreground {
+                color: C::expand(self.foreground),
+                character: self.character,
+            }),
+            attributes: Attributes::from_bits(self.flags),
+        }
+    }
+}
+
+/// An `AsciiImage` which stores its cells as `PackedCell`s,
+/// using less memory at the cost of unpacking the cells when they are used.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PackedImage<C: CompactColor> {
+    width: u32,
+    cells: Vec<PackedCell<C::Compact>>,
+    color: PhantomData<C>,
+}
+
+impl<C: CompactColor> PackedImage<C> {
+    /// The width of the image in characters
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image in characters
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        (self.cells.len() as u64)
+            .checked_div(self.width as u64)
+            .unwrap_or_default()
+            .try_into()
+            .unwrap_or(u32::MAX)
+    }
+
+    /// All the cells that make up the image.
+    /// Going from left to right, wrapping from top to bottom.
+    #[must_use]
+    pub fn cells(&self) -> &[PackedCell<C::Compact>] {
+        &self.cells
+    }
+
+    /// Gets the cell at (`x`, `y`), or `None` if the coordinates are out of bounds.
+    #[must_use]
+    pub fn get(&self, x: u32, y: u32) -> Option<AsciiCell<C>> {
+        if self.width <= x || self.height() <= y {
+            return None;
+        }
+
+        let index: usize = (self.width as u64 * y as u64 + x as u64).try_into().ok()?;
+        Some(self.cells[index].unpack())
+    }
+
+    /// Converts the image back to an `AsciiImage`.
+    #[must_use]
+    pub fn unpack(&self) -> AsciiImage<C> {
+        let cells = self.cells.iter().map(PackedCell::unpack);
+
+        // The number of cells always matches the dimensions.
+        AsciiImage::from_cells(cells, self.width, self.height()).unwrap_or_default()
+    }
+}
+
+impl<C: CompactColor> AsciiImage<C> {
+    /// Converts the image to a `PackedImage`.
+    #[must_use]
+    pub fn pack(&self) -> PackedImage<C> {
+        PackedImage {
+            width: self.width(),
+            cells: self.cells().iter().map(PackedCell::pack).collect(),
+            color: PhantomData,
+        }
+    }
+}
+
+impl<C: CompactColor + PartialEq> Display for PackedImage<C> {
+    /// Formats the image like an `AsciiImage`, unpacking one line at a time.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // `chunks` panics on a chunk size of zero, but an image without width has no cells anyway.
+        for (y, row) in self.cells.chunks(self.width.max(1) as usize).enumerate() {
+            if y != 0 {
+                f.write_str("\n")?;
+            }
+
+            let cells = row.iter().map(PackedCell::unpack);
+            let line = AsciiImage::<C>::from_cells(cells, self.width, 1).map_err(|_| std::fmt::Error)?;
+
+            // `line` has exactly one line.
+            f.write_str(&line.line(0).unwrap_or_default())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<C: CompactColor> From<&AsciiImage<C>> for PackedImage<C> {
+    fn from(image: &AsciiImage<C>) -> Self {
+        image.pack()
+    }
+}
+
+impl<C: CompactColor> From<&PackedImage<C>> for AsciiImage<C> {
+    fn from(image: &PackedImage<C>) -> Self {
+        image.unpack()
+    }
+}
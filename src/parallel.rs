@@ -0,0 +1,34 @@
+//! Bounds for the types shared between the threads of a conversion.
+//!
+//! With the `rayon` feature the cells are converted in parallel,
+//! so images, fonts and renderers need to be `Sync` and colors need to be `Send`.
+//! Without it these traits are implemented for every type,
+//! so that the conversions work for any type.
+
+/// `Send` if the `rayon` feature is enabled, implemented for every type otherwise.
+#[cfg(feature = "rayon")]
+pub trait MaybeSend: Send {}
+
+#[cfg(feature = "rayon")]
+impl<T: Send + ?Sized> MaybeSend for T {}
+
+/// `Send` if the `rayon` feature is enabled, implemented for every type otherwise.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSend {}
+
+#[cfg(not(feature = "rayon"))]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// `Sync` if the `rayon` feature is enabled, implemented for every type otherwise.
+#[cfg(feature = "rayon")]
+pub trait MaybeSync: Sync {}
+
+#[cfg(feature = "rayon")]
+impl<T: Sync + ?Sized> MaybeSync for T {}
+
+/// `Sync` if the `rayon` feature is enabled, implemented for every type otherwise.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSync {}
+
+#[cfg(not(feature = "rayon"))]
+impl<T: ?Sized> MaybeSync for T {}
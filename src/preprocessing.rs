@@ -0,0 +1,276 @@
+use crate::color::util::normalize;
+use image::imageops::blur;
+use image::{DynamicImage, GenericImageView, ImageBuffer, Pixel, Rgba};
+
+const BINS: usize = 256;
+
+/// Adjustments applied to an image before it is converted to ascii.
+///
+/// They are applied in the order of the fields.
+/// The channels are treated as values on the interval [0; 1].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Preprocessing {
+    /// Spreads out the luminance of the image.
+    pub equalization: Option<Equalization>,
+    /// Sharpens the image.
+    pub unsharp_mask: Option<UnsharpMask>,
+    /// Added to every channel. `0` leaves the image as is.
+    pub brightness: f32,
+    /// Scales the distance from middle gray. `1` leaves the image as is.
+    pub contrast: f32,
+    /// Every channel is raised to the power of `1 / gamma`,
+    /// meaning values above `1` brighten the image. `1` leaves the image as is.
+    pub gamma: f32,
+    /// Scales the distance from gray. `0` makes the image grayscale, `1` leaves it as is.
+    pub saturation: f32,
+    /// Inverts the colors, which is useful for light terminals.
+    pub invert: bool,
+}
+
+/// Histogram equalization of the luminance.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Equalization {
+    /// Equalizes the histogram of the whole image.
+    Global,
+    /// Contrast limited adaptive histogram equalization (CLAHE).
+    /// The image is split into `tiles × tiles` tiles which are equalized separately.
+    /// Every bin of a tile's histogram is limited to `clip_limit` times the average bin,
+    /// which keeps noise in flat regions from being amplified.
+    Adaptive { tiles: u32, clip_limit: f32 },
+}
+
+/// Sharpens the image by adding the difference between it and a blurred version of it.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct UnsharpMask {
+    /// The standard deviation of the blur.
+    pub sigma: f32,
+    /// The smallest difference between a channel and its blurred value that is sharpened,
+    /// on the same interval [0; 1] as the channels.
+    pub threshold: f32,
+}
+
+impl Default for Preprocessing {
+    fn default() -> Self {
+        Preprocessing::NONE
+    }
+}
+
+impl Preprocessing {
+    /// Preprocessing which leaves the image as is.
+    pub const NONE: Preprocessing = Preprocessing {
+        equalization: None,
+        unsharp_mask: None,
+        brightness: 0.0,
+        contrast: 1.0,
+        gamma: 1.0,
+        saturation: 1.0,
+        invert: false,
+    };
+
+    /// Whether the preprocessing leaves the image as is.
+    #[must_use]
+    pub fn is_none(&self) -> bool {
+        *self == Preprocessing::NONE
+    }
+
+    /// Applies the preprocessing to `image`.
+    #[must_use]
+    pub fn apply(&self, image: &impl GenericImageView) -> DynamicImage {
+        let mut image = ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+            Rgba(image.get_pixel(x, y).to_rgba().0.map(normalize))
+        });
+
+        if let Some(equalization) = self.equalization {
+            equalization.apply(&mut image);
+        }
+
+        if let Some(unsharp_mask) = self.unsharp_mask {
+            unsharp_mask.apply(&mut image);
+        }
+
+        for Rgba([r, g, b, _]) in image.pixels_mut() {
+            let mut rgb = [*r, *g, *b];
+
+            for channel in &mut rgb {
+                *channel += self.brightness;
+                *channel = (*channel - 0.5) * self.contrast + 0.5;
+                *channel = channel.clamp(0.0, 1.0).powf(self.gamma.recip());
+            }
+
+            let luma = luma(rgb);
+            for channel in &mut rgb {
+                *channel = luma + (*channel - luma) * self.saturation;
+
+                if self.invert {
+                    *channel = 1.0 - *channel;
+                }
+            }
+
+            [*r, *g, *b] = rgb.map(|channel| channel.clamp(0.0, 1.0));
+        }
+
+        DynamicImage::ImageRgba8(DynamicImage::ImageRgba32F(image).into_rgba8())
+    }
+}
+
+impl UnsharpMask {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<f32>, Vec<f32>>) {
+        if image.width() == 0 || image.height() == 0 {
+            return;
+        }
+
+        let blurred = blur(image, self.sigma);
+
+        for (pixel, blurred) in image.pixels_mut().zip(blurred.pixels()) {
+            // The alpha channel is left as is.
+            for (channel, blurred) in pixel.0.iter_mut().zip(blurred.0).take(3) {
+                let difference = *channel - blurred;
+
+                if self.threshold <= difference.abs() {
+                    *channel = (*channel + difference).clamp(0.0, 1.0);
+                }
+            }
+        }
+    }
+}
+
+impl Equalization {
+    fn apply(&self, image: &mut ImageBuffer<Rgba<f32>, Vec<f32>>) {
+        let (width, height) = image.dimensions();
+
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        match *self {
+            Equalization::Global => {
+                let histogram = histogram(image, 0..width, 0..height);
+                let mapping = mapping(&histogram);
+
+                for pixel in image.pixels_mut() {
+                    set_luma(pixel, mapping[bin(pixel)]);
+                }
+            }
+            Equalization::Adaptive { tiles, clip_limit } => {
+                let tiles = tiles.clamp(1, width.min(height));
+                let tile_width = width.div_ceil(tiles);
+                let tile_height = height.div_ceil(tiles);
+
+                let mut mappings = Vec::with_capacity((tiles * tiles) as usize);
+
+                for tile_y in 0..tiles {
+                    for tile_x in 0..tiles {
+                        let xs = tile_x * tile_width..((tile_x + 1) * tile_width).min(width);
+                        let ys = tile_y * tile_height..((tile_y + 1) * tile_height).min(height);
+
+                        let mut histogram = histogram(image, xs, ys);
+                        clip(&mut histogram, clip_limit);
+                        mappings.push(mapping(&histogram));
+                    }
+                }
+
+                // Where the pixel lies between the centers of the tiles,
+                // as the indices of the two closest tiles and the weight of the latter.
+                let locate = |position: u32, tile_size: u32| {
+                    let position = (position as f32 + 0.5) / tile_size as f32 - 0.5;
+                    let first = (position.floor().max(0.0) as u32).min(tiles - 1);
+                    let second = (first + 1).min(tiles - 1);
+                    (first, second, (position - first as f32).clamp(0.0, 1.0))
+                };
+
+                for (x, y, pixel) in image.enumerate_pixels_mut() {
+                    let bin = bin(pixel);
+
+                    let (left, right, horizontal) = locate(x, tile_width);
+                    let (top, bottom, vertical) = locate(y, tile_height);
+
+                    let map = |tile_x: u32, tile_y: u32| mappings[(tile_y * tiles + tile_x) as usize][bin];
+                    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+                    let luma = lerp(
+                        lerp(map(left, top), map(right, top), horizontal),
+                        lerp(map(left, bottom), map(right, bottom), horizontal),
+                        vertical,
+                    );
+
+                    set_luma(pixel, luma);
+                }
+            }
+        }
+    }
+}
+
+fn luma([r, g, b]: [f32; 3]) -> f32 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+fn bin(Rgba([r, g, b, _]): &Rgba<f32>) -> usize {
+    (luma([*r, *g, *b]).clamp(0.0, 1.0) * (BINS - 1) as f32).round() as usize
+}
+
+/// Scales the color channels so that the pixel gets the provided luminance, keeping its hue.
+fn set_luma(Rgba([r, g, b, _]): &mut Rgba<f32>, new: f32) {
+    let old = luma([*r, *g, *b]);
+
+    if old <= 0.0 {
+        [*r, *g, *b] = [new; 3];
+    } else {
+        let scale = new / old;
+        [*r, *g, *b] = [*r, *g, *b].map(|channel| (channel * scale).clamp(0.0, 1.0));
+    }
+}
+
+fn histogram(
+    image: &ImageBuffer<Rgba<f32>, Vec<f32>>,
+    xs: std::ops::Range<u32>,
+    ys: std::ops::Range<u32>,
+) -> [f32; BINS] {
+    let mut histogram = [0.0; BINS];
+
+    for y in ys {
+        for x in xs.clone() {
+            histogram[bin(image.get_pixel(x, y))] += 1.0;
+        }
+    }
+
+    histogram
+}
+
+/// Limits every bin to `clip_limit` times the average and spreads the excess evenly.
+fn clip(histogram: &mut [f32; BINS], clip_limit: f32) {
+    let total: f32 = histogram.iter().sum();
+    let limit = (clip_limit * total / BINS as f32).max(1.0);
+
+    let mut excess = 0.0;
+    for count in histogram.iter_mut() {
+        if limit < *count {
+            excess += *count - limit;
+            *count = limit;
+        }
+    }
+
+    for count in histogram.iter_mut() {
+        *count += excess / BINS as f32;
+    }
+}
+
+/// Maps every bin to its new luminance using the cumulative distribution.
+fn mapping(histogram: &[f32; BINS]) -> [f32; BINS] {
+    let total: f32 = histogram.iter().sum();
+    let first = histogram.iter().copied().find(|&count| 0.0 < count).unwrap_or(0.0);
+
+    let mut mapping = [0.0; BINS];
+    let mut cumulative = 0.0;
+
+    for (bin, count) in histogram.iter().enumerate() {
+        cumulative += count;
+
+        mapping[bin] = if total <= first {
+            bin as f32 / (BINS - 1) as f32
+        } else {
+            ((cumulative - first) / (total - first)).clamp(0.0, 1.0)
+        };
+    }
+
+    mapping
+}
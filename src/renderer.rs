@@ -0,0 +1,42 @@
+use crate::cell::AsciiCell;
+use crate::color::util::average_color;
+use crate::conversion::Conversion;
+use crate::font::Font;
+use image::{GenericImageView, Pixel, Rgb};
+
+/// A strategy for drawing the pixels under a cell as an `AsciiCell` with colors of type `C`.
+///
+/// This is separate from [`Color`](crate::color::Color),
+/// which only describes how a color is encoded,
+/// so that any rendering style may be used with any palette.
+pub trait CellRenderer<C> {
+    /// Creates a cell which approximates `color`.
+    #[must_use]
+    fn render_color<G: AsRef<[char]>>(
+        &self,
+        color: Rgb<u8>,
+        font: &Font<G>,
+        conversion: &Conversion,
+    ) -> AsciiCell<C>;
+
+    // TODO: Take alpha channel into consideration.
+    /// Creates a cell which approximates the pixels in `view`.
+    /// By default the pixels are averaged and passed to `render_color`.
+    #[must_use]
+    fn render_cell<I: GenericImageView, G: AsRef<[char]>>(
+        &self,
+        view: &I,
+        font: &Font<G>,
+        conversion: &Conversion,
+    ) -> AsciiCell<C> {
+        self.render_color(average_color(view, conversion.linear_light).to_rgb(), font, conversion)
+    }
+}
+
+/// The default renderer, which finds the combination of background, foreground
+/// and character from the font's gradient that is closest to the color of the cell.
+///
+/// Colors that can represent any color exactly are drawn as a background only,
+/// and colorless cells pick a character based on the luminance.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct GradientMatcher;
@@ -0,0 +1,146 @@
+//! `Serialize` and `Deserialize` implementations, enabled by the `serde` feature.
+//!
+//! Colors from a palette are stored as their index in it
+//! (the 256-color index for 8-bit colors and the color code for mIRC colors)
+//! and 24-bit colors as `"#rrggbb"` strings.
+
+use crate::cell::AsciiCell;
+use crate::color::ansi_24_bit::Ansi24Bit;
+use crate::color::ansi_3_bit::Ansi3Bit;
+use crate::color::ansi_4_bit::Ansi4Bit;
+use crate::color::ansi_8_bit::cube::Cube;
+use crate::color::ansi_8_bit::grayscale::Grayscale;
+use crate::color::ansi_8_bit::Ansi8Bit;
+use crate::color::compact::CompactColor;
+use crate::color::mirc::{self, Mirc};
+use crate::color::Color;
+use crate::font::Font;
+use crate::image::AsciiImage;
+use image::Rgb;
+use num_rational::Ratio;
+use serde::de::Error;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Implements `Serialize` and `Deserialize` for a color with `u8` as its compact representation,
+/// where the valid values are less than `$count`.
+macro_rules! serialize_as_index {
+    ($color:ty, $count:expr) => {
+        impl Serialize for $color {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.compact().serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $color {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let index = u16::deserialize(deserializer)?;
+
+                if $count <= index {
+                    return Err(D::Error::custom(format_args!(
+                        "color index {index} is out of range, expected less than {}",
+                        $count
+                    )));
+                }
+
+                Ok(<$color>::expand(index as u8))
+            }
+        }
+    };
+}
+
+serialize_as_index!(Ansi3Bit, 8);
+serialize_as_index!(Ansi4Bit, 16);
+serialize_as_index!(Ansi8Bit, 256);
+serialize_as_index!(Cube, 216);
+serialize_as_index!(Grayscale, 24);
+serialize_as_index!(Mirc, mirc::COUNT as u16);
+
+impl Serialize for Ansi24Bit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Rgb([r, g, b]) = self.to_rgb();
+        serializer.collect_str(&format_args!("#{r:02x}{g:02x}{b:02x}"))
+    }
+}
+
+impl<'de> Deserialize<'de> for Ansi24Bit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+
+        let color = hex
+            .strip_prefix('#')
+            .filter(|digits| digits.len() == 6)
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| D::Error::custom(format_args!("invalid color {hex:?}, expected \"#rrggbb\"")))?;
+
+        let [_, r, g, b] = color.to_be_bytes();
+        Ok(Ansi24Bit::new(r, g, b))
+    }
+}
+
+impl<C: Serialize> Serialize for AsciiImage<C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut image = serializer.serialize_struct("AsciiImage", 2)?;
+        image.serialize_field("width", &self.width())?;
+        image.serialize_field("cells", self.cells())?;
+        image.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "AsciiImage")]
+struct ImageData<C> {
+    width: u32,
+    cells: Vec<AsciiCell<C>>,
+}
+
+impl<'de, C: Deserialize<'de>> Deserialize<'de> for AsciiImage<C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ImageData { width, cells } = ImageData::deserialize(deserializer)?;
+
+        let length = cells.len() as u64;
+        let height = length.checked_div(width as u64).unwrap_or_default();
+
+        if height * width as u64 != length {
+            return Err(D::Error::custom(format_args!(
+                "{length} cells don't fill rows of width {width}"
+            )));
+        }
+
+        let height = height.try_into().map_err(D::Error::custom)?;
+
+        AsciiImage::from_cells(cells, width, height)
+            .map_err(|_| D::Error::custom("the cells don't match the dimensions"))
+    }
+}
+
+impl<G: AsRef<[char]>> Serialize for Font<G> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut font = serializer.serialize_struct("Font", 3)?;
+        font.serialize_field("gradient", self.gradient())?;
+        font.serialize_field("max_coverage", &self.max_coverage())?;
+        font.serialize_field("aspect_ratio", &self.aspect_ratio())?;
+        font.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Font")]
+struct FontData<G> {
+    gradient: G,
+    max_coverage: Ratio<u32>,
+    aspect_ratio: Ratio<u32>,
+}
+
+impl<'de, G: AsRef<[char]> + Deserialize<'de>> Deserialize<'de> for Font<G> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let font = FontData::deserialize(deserializer)?;
+
+        Font::new(font.gradient, font.max_coverage, font.aspect_ratio).map_err(|_| {
+            D::Error::custom(
+                "invalid font, the gradient must not be empty, \
+                 the max coverage must be at most 1 and the aspect ratio must not be 0",
+            )
+        })
+    }
+}
@@ -0,0 +1,106 @@
+use crate::cell::AsciiCell;
+use num_rational::Ratio;
+
+/// How an image is sized to a bounding box of characters.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Sizing<Color> {
+    /// The image is scaled to fit inside the box, keeping its aspect ratio.
+    /// The result may be narrower or shorter than the box.
+    Fit,
+    /// The image is scaled to cover the box, keeping its aspect ratio.
+    /// The parts that don't fit are cropped, keeping the side given by the gravity.
+    Fill(Gravity),
+    /// The image is scaled to exactly the size of the box, ignoring its aspect ratio.
+    Stretch,
+    /// Like `Fit`, but the rest of the box is filled with `padding`.
+    /// The image is placed in the box according to `gravity`.
+    Letterbox {
+        padding: AsciiCell<Color>,
+        gravity: Gravity,
+    },
+}
+
+/// Where an image is anchored within a larger area.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum Gravity {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Gravity {
+    /// The distance between the top left corners of two rectangles
+    /// when the smaller one is anchored within the larger one.
+    #[must_use]
+    pub fn offset(&self, (outer_width, outer_height): (u32, u32), (inner_width, inner_height): (u32, u32)) -> (u32, u32) {
+        let (horizontal, vertical) = self.fractions();
+
+        let offset = |fraction: u64, outer: u32, inner: u32| (outer.abs_diff(inner) as u64 * fraction / 2) as u32;
+
+        (
+            offset(horizontal, outer_width, inner_width),
+            offset(vertical, outer_height, inner_height),
+        )
+    }
+
+    /// How far along each axis the image is anchored, in halves.
+    fn fractions(&self) -> (u64, u64) {
+        match self {
+            Gravity::TopLeft => (0, 0),
+            Gravity::Top => (1, 0),
+            Gravity::TopRight => (2, 0),
+            Gravity::Left => (0, 1),
+            Gravity::Center => (1, 1),
+            Gravity::Right => (2, 1),
+            Gravity::BottomLeft => (0, 2),
+            Gravity::Bottom => (1, 2),
+            Gravity::BottomRight => (2, 2),
+        }
+    }
+}
+
+/// The dimensions in characters that an image of `image_width` × `image_height` pixels
+/// is converted to before being cropped or padded to the bounding box.
+///
+/// `aspect_ratio` is the font width divided by the font height.
+/// An image or box without area is converted to no cells at all.
+pub(crate) fn scaled_dimensions<C>(
+    sizing: &Sizing<C>,
+    (image_width, image_height): (u32, u32),
+    aspect_ratio: Ratio<u32>,
+    (width, height): (u32, u32),
+) -> (u32, u32) {
+    if image_width == 0 || image_height == 0 || width == 0 || height == 0 {
+        return (0, 0);
+    }
+
+    // The height of the image in characters per character of width.
+    let (aspect_width, aspect_height) = aspect_ratio.into_raw();
+    let ratio = Ratio::new(
+        image_height as u64 * aspect_width as u64,
+        image_width as u64 * aspect_height as u64,
+    );
+
+    let width_limited = ratio * width as u64 <= Ratio::from_integer(height as u64);
+
+    let cover = match sizing {
+        Sizing::Stretch => return (width, height),
+        Sizing::Fit | Sizing::Letterbox { .. } => false,
+        Sizing::Fill(_) => true,
+    };
+
+    let to_u32 = |value: Ratio<u64>| value.round().to_integer().try_into().unwrap_or(u32::MAX);
+
+    if width_limited != cover {
+        (width, to_u32(ratio * width as u64).max(1))
+    } else {
+        (to_u32(Ratio::from_integer(height as u64) / ratio).max(1), height)
+    }
+}
@@ -0,0 +1,240 @@
+use crate::color::Color;
+use crate::conversion::Conversion;
+use crate::font::Font;
+use crate::image::AsciiImage;
+use crate::parallel::{MaybeSend, MaybeSync};
+use crate::renderer::{CellRenderer, GradientMatcher};
+use crate::sizing::Sizing;
+use image::GenericImageView;
+
+/// The dimensions of a terminal in characters.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TerminalSize {
+    pub columns: u32,
+    pub rows: u32,
+}
+
+impl TerminalSize {
+    /// Queries the size of the terminal attached to stdout, stderr or stdin.
+    /// If none of them are terminals, the `COLUMNS` and `LINES` environment variables are used.
+    /// Returns `None` if the size couldn't be determined.
+    #[must_use]
+    pub fn query() -> Option<TerminalSize> {
+        Self::from_ioctl().or_else(Self::from_environment)
+    }
+
+    #[cfg(unix)]
+    fn from_ioctl() -> Option<TerminalSize> {
+        [libc::STDOUT_FILENO, libc::STDERR_FILENO, libc::STDIN_FILENO]
+            .into_iter()
+            .find_map(|descriptor| {
+                let mut size = libc::winsize {
+                    ws_row: 0,
+                    ws_col: 0,
+                    ws_xpixel: 0,
+                    ws_ypixel: 0,
+                };
+
+                // SAFETY: `TIOCGWINSZ` only writes a `winsize` to the provided pointer.
+                let result = unsafe { libc::ioctl(descriptor, libc::TIOCGWINSZ, &mut size) };
+
+                (result == 0 && size.ws_col != 0 && size.ws_row != 0).then_some(TerminalSize {
+                    columns: size.ws_col as u32,
+                    rows: size.ws_row as u32,
+                })
+            })
+    }
+
+    #[cfg(not(unix))]
+    fn from_ioctl() -> Option<TerminalSize> {
+        None
+    }
+
+    fn from_environment() -> Option<TerminalSize> {
+        let columns = std::env::var("COLUMNS").ok();
+        let lines = std::env::var("LINES").ok();
+
+        Self::from_variables(columns.as_deref(), lines.as_deref())
+    }
+
+    /// Parses the values of the `COLUMNS` and `LINES` environment variables,
+    /// which both have to be set to a positive number.
+    pub(crate) fn from_variables(columns: Option<&str>, lines: Option<&str>) -> Option<TerminalSize> {
+        let parse = |value: Option<&str>| value?.trim().parse().ok().filter(|&value| value != 0);
+
+        Some(TerminalSize {
+            columns: parse(columns)?,
+            rows: parse(lines)?,
+        })
+    }
+
+    /// The part of the terminal above the bottom `reserved_rows` rows.
+    pub(crate) fn reserving(self, reserved_rows: u32) -> TerminalSize {
+        TerminalSize {
+            columns: self.columns,
+            rows: self.rows.saturating_sub(reserved_rows),
+        }
+    }
+}
+
+impl<C: Color + MaybeSend> AsciiImage<C>
+where
+    GradientMatcher: CellRenderer<C>,
+{
+    /// Converts the image to ascii so that it fits the terminal, as described by `sizing`.
+    /// `reserved_rows` rows are left free at the bottom, e.g. for a prompt.
+    ///
+    /// Returns `None` if the size of the terminal couldn't be determined.
+    pub fn from_image_for_terminal<I, G>(
+        image: &I,
+        font: &Font<G>,
+        conversion: &Conversion,
+        sizing: &Sizing<C>,
+        reserved_rows: u32,
+    ) -> Option<Self>
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+    {
+        Self::from_image_for_terminal_with_renderer(
+            image,
+            font,
+            conversion,
+            &GradientMatcher,
+            sizing,
+            reserved_rows,
+        )
+    }
+}
+
+impl<C: Color + MaybeSend> AsciiImage<C> {
+    /// Like `from_image_for_terminal`, but every cell is drawn using `renderer`.
+    pub fn from_image_for_terminal_with_renderer<I, G, R>(
+        image: &I,
+        font: &Font<G>,
+        conversion: &Conversion,
+        renderer: &R,
+        sizing: &Sizing<C>,
+        reserved_rows: u32,
+    ) -> Option<Self>
+    where
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+        R: CellRenderer<C> + MaybeSync,
+    {
+        let size = TerminalSize::query()?.reserving(reserved_rows);
+
+        Some(Self::from_image_with_sizing_and_renderer(
+            image,
+            font,
+            conversion,
+            renderer,
+            sizing,
+            size.columns,
+            size.rows,
+        ))
+    }
+}
+
+/// Listens for the terminal being resized (`SIGWINCH`),
+/// so that long-running viewers know when to render the image again.
+#[cfg(unix)]
+pub struct ResizeWatcher {
+    signals: signal_hook::iterator::Signals,
+}
+
+#[cfg(unix)]
+impl ResizeWatcher {
+    /// Starts listening for resizes.
+    ///
+    /// # Errors
+    /// If the signal handler couldn't be registered.
+    pub fn new() -> std::io::Result<ResizeWatcher> {
+        Ok(ResizeWatcher {
+            signals: signal_hook::iterator::Signals::new([libc::SIGWINCH])?,
+        })
+    }
+
+    /// Whether the terminal has been resized since the last call to `resized` or `wait`.
+    pub fn resized(&mut self) -> bool {
+        self.signals.pending().count() != 0
+    }
+
+    /// Blocks until the terminal is resized.
+    pub fn wait(&mut self) {
+        while self.signals.wait().count() == 0 {}
+    }
+
+    /// Renders the image to fit the terminal using `render`, and renders it again
+    /// every time the terminal is resized, for as long as `render` returns `Ok(true)`.
+    ///
+    /// # Errors
+    /// If the size of the terminal can't be determined
+    /// or `render` fails, the error is returned.
+    pub fn render_on_resize<C, I, G>(
+        &mut self,
+        image: &I,
+        font: &Font<G>,
+        conversion: &Conversion,
+        sizing: &Sizing<C>,
+        reserved_rows: u32,
+        render: impl FnMut(&AsciiImage<C>) -> std::io::Result<bool>,
+    ) -> std::io::Result<()>
+    where
+        C: Color + MaybeSend,
+        GradientMatcher: CellRenderer<C>,
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+    {
+        self.render_on_resize_with_renderer(
+            image,
+            font,
+            conversion,
+            &GradientMatcher,
+            sizing,
+            reserved_rows,
+            render,
+        )
+    }
+
+    /// Like `render_on_resize`, but every cell is drawn using `renderer`.
+    ///
+    /// # Errors
+    /// If the size of the terminal can't be determined
+    /// or `render` fails, the error is returned.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_on_resize_with_renderer<C, I, G, R>(
+        &mut self,
+        image: &I,
+        font: &Font<G>,
+        conversion: &Conversion,
+        renderer: &R,
+        sizing: &Sizing<C>,
+        reserved_rows: u32,
+        mut render: impl FnMut(&AsciiImage<C>) -> std::io::Result<bool>,
+    ) -> std::io::Result<()>
+    where
+        C: Color + MaybeSend,
+        I: GenericImageView + MaybeSync,
+        G: AsRef<[char]> + MaybeSend + MaybeSync,
+        R: CellRenderer<C> + MaybeSync,
+    {
+        loop {
+            let ascii = AsciiImage::from_image_for_terminal_with_renderer(
+                image,
+                font,
+                conversion,
+                renderer,
+                sizing,
+                reserved_rows,
+            )
+            .ok_or_else(|| std::io::Error::other("couldn't determine the size of the terminal"))?;
+
+            if !render(&ascii)? {
+                return Ok(());
+            }
+
+            self.wait();
+        }
+    }
+}
@@ -1 +1,1783 @@
+use crate::cell::{AsciiCell, Attributes, Foreground};
+use crate::color::ansi_8_bit::Ansi8Bit;
+use crate::color::Color;
+use crate::image::AsciiImage;
+use std::collections::HashSet;
+use std::fmt::Debug;
 
+/// A cell drawing `character` in `foreground` on `background`.
+fn cell<C>(character: char, foreground: C, background: C) -> AsciiCell<C> {
+    AsciiCell {
+        background,
+        foreground: Some(Foreground {
+            color: foreground,
+            character,
+        }),
+        attributes: Attributes::NONE,
+    }
+}
+
+/// A cell which only shows its background.
+fn blank<C>(background: C) -> AsciiCell<C> {
+    AsciiCell {
+        background,
+        foreground: None,
+        attributes: Attributes::NONE,
+    }
+}
+
+/// An image of the characters in `text`, drawn in color 1 on color 0, with spaces as blank cells.
+fn text_image(width: u32, text: &str) -> AsciiImage<Ansi8Bit> {
+    let cells: Vec<_> = text
+        .chars()
+        .map(|character| match character {
+            ' ' => blank(Ansi8Bit::from_index(0)),
+            character => cell(character, Ansi8Bit::from_index(1), Ansi8Bit::from_index(0)),
+        })
+        .collect();
+    let height = cells.len() as u32 / width.max(1);
+
+    AsciiImage::from_cells(cells, width, height).unwrap()
+}
+
+/// A `width` × 2 image of a row of blank cells above a row of bold `é`s.
+fn two_rows<C: Copy + Debug>(width: u32, foreground: C, background: C) -> AsciiImage<C> {
+    let bold = AsciiCell {
+        attributes: Attributes::BOLD,
+        ..cell('é', foreground, background)
+    };
+    let cells = (0..width * 2).map(|index| if index < width { blank(background) } else { bold });
+
+    AsciiImage::from_cells(cells, width, 2).unwrap()
+}
+
+/// The characters of the image, with blank cells as spaces.
+fn text<C>(image: &AsciiImage<C>) -> String {
+    image
+        .cells()
+        .iter()
+        .map(|cell| cell.foreground.as_ref().map_or(' ', |foreground| foreground.character))
+        .collect()
+}
+
+#[test]
+fn ansi_8_bit_index_round_trip() {
+    for index in 0..=u8::MAX {
+        assert_eq!(Ansi8Bit::from_index(index).index(), index);
+    }
+}
+
+#[test]
+fn ansi_8_bit_indices_are_distinct() {
+    let colors: HashSet<Ansi8Bit> = (0..=u8::MAX).map(Ansi8Bit::from_index).collect();
+    assert_eq!(colors.len(), 256);
+}
+
+#[test]
+fn ansi_8_bit_escape_code_uses_index() {
+    for index in 0..=u8::MAX {
+        let color = Ansi8Bit::from_index(index);
+
+        let mut background = Vec::new();
+        color.write_background(&mut background).unwrap();
+
+        // 4-bit colors are written using their own escape codes.
+        if 16 <= index {
+            assert_eq!(background, [48, 5, index]);
+        }
+    }
+}
+
+#[test]
+fn ansi_8_bit_order_follows_index() {
+    let mut colors: Vec<Ansi8Bit> = (0..=u8::MAX).rev().map(Ansi8Bit::from_index).collect();
+    colors.sort();
+
+    assert!(colors.iter().map(Ansi8Bit::index).eq(0..=u8::MAX));
+}
+
+#[test]
+fn palettes_list_every_color_once() {
+    use crate::color::ansi_8_bit::grayscale::Grayscale;
+    use crate::color::variants::{ANSI_3_BIT, ANSI_4_BIT, ANSI_8_BIT, CUBE, GRAYSCALE};
+
+    assert!(ANSI_8_BIT.iter().map(Ansi8Bit::index).eq(0..=u8::MAX));
+    assert!(GRAYSCALE.iter().map(Grayscale::brightness).eq(0..24));
+    assert_eq!(CUBE.iter().collect::<HashSet<_>>().len(), CUBE.len());
+    assert_eq!(ANSI_4_BIT.iter().collect::<HashSet<_>>().len(), ANSI_4_BIT.len());
+    assert_eq!(ANSI_3_BIT.iter().collect::<HashSet<_>>().len(), ANSI_3_BIT.len());
+}
+
+mod editing {
+    use super::{blank, cell, text, text_image};
+    use crate::color::ansi_8_bit::Ansi8Bit;
+    use crate::image::AsciiImage;
+
+    fn image() -> AsciiImage<Ansi8Bit> {
+        text_image(3, "abcdef")
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut image = image();
+        assert_eq!(image.get(2, 1).unwrap().foreground.unwrap().character, 'f');
+        assert_eq!(image.get(3, 0), None);
+        assert_eq!(image.get(0, 2), None);
+
+        let replaced = image.set(1, 0, blank(Ansi8Bit::from_index(2))).unwrap();
+        assert_eq!(replaced.foreground.unwrap().character, 'b');
+        assert_eq!(text(&image), "a cdef");
+
+        let rejected = blank(Ansi8Bit::from_index(3));
+        assert_eq!(image.set(0, 2, rejected), Err(rejected));
+    }
+
+    #[test]
+    fn rows_and_coordinates() {
+        let image = image();
+        assert_eq!(image.rows().len(), 2);
+        assert_eq!(image.rows().last().unwrap()[0].foreground.unwrap().character, 'd');
+
+        let coordinates: Vec<_> = image.enumerate_cells().map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(coordinates, [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn fill_rect_is_clipped() {
+        let mut image = image();
+        image.fill_rect(1, 1, 10, 10, cell('#', Ansi8Bit::from_index(1), Ansi8Bit::from_index(0)));
+        assert_eq!(text(&image), "abcd##");
+
+        image.fill_rect(3, 0, 1, 1, blank(Ansi8Bit::from_index(0)));
+        assert_eq!(text(&image), "abcd##");
+    }
+
+    #[test]
+    fn empty_image() {
+        let mut image = AsciiImage::<Ansi8Bit>::new();
+        assert_eq!(image.height(), 0);
+        assert_eq!(image.get(0, 0), None);
+        assert_eq!(image.rows().len(), 0);
+
+        image.fill_rect(0, 0, 2, 2, blank(Ansi8Bit::from_index(0)));
+        assert_eq!(image, AsciiImage::new());
+        assert_eq!(image.to_string(), "");
+
+        let no_width = AsciiImage::filled(0, 5, blank(Ansi8Bit::from_index(0)));
+        assert_eq!((no_width.width(), no_width.height()), (0, 0));
+    }
+}
+
+mod compose {
+    use super::{blank, cell, text, text_image};
+    use crate::color::ansi_8_bit::Ansi8Bit;
+    use crate::image::AsciiImage;
+
+    #[test]
+    fn crop_is_clipped() {
+        let image = text_image(3, "abcdefghi");
+        assert_eq!(text(&image.crop(1, 1, 5, 5)), "efhi");
+        assert_eq!(image.crop(1, 1, 5, 5).width(), 2);
+
+        let outside = image.crop(3, 0, 2, 2);
+        assert_eq!(outside, AsciiImage::new());
+        assert_eq!(outside.crop(0, 0, 1, 1), AsciiImage::new());
+    }
+
+    #[test]
+    fn paste_at_negative_offset() {
+        let mut image = text_image(3, "abcdefghi");
+        image.paste_at(&text_image(2, "wxyz"), -1, 2);
+        assert_eq!(text(&image), "abcdefxhi");
+    }
+
+    #[test]
+    fn overlay_transparency() {
+        let is_transparent = |color: &Ansi8Bit| color.index() == 0;
+
+        let mut image = text_image(3, "abc");
+        let mut upper = text_image(3, "xyz");
+        upper.set(1, 0, blank(Ansi8Bit::from_index(0))).unwrap();
+        upper.set(2, 0, cell('z', Ansi8Bit::from_index(1), Ansi8Bit::from_index(5))).unwrap();
+        image.overlay(&upper, 0, 0, is_transparent);
+
+        // Characters on a transparent background keep the background below, others cover the cell.
+        assert_eq!(text(&image), "xbz");
+        assert_eq!(image.get(0, 0).unwrap().background.index(), 0);
+        assert_eq!(image.get(2, 0).unwrap().background.index(), 5);
+    }
+
+    #[test]
+    fn blank_cells_are_see_through() {
+        let mut image = text_image(2, "ab");
+        let upper = AsciiImage::filled(2, 1, blank(Ansi8Bit::from_index(5)));
+        image.overlay(&upper, 0, 0, |_| false);
+
+        assert_eq!(image, text_image(2, "ab"));
+    }
+
+    #[test]
+    fn concatenation() {
+        let padding = blank(Ansi8Bit::from_index(0));
+        let tall = text_image(1, "ab");
+        let wide = text_image(2, "cd");
+
+        let horizontal = AsciiImage::concat_horizontal([&tall, &wide], padding);
+        assert_eq!((horizontal.width(), text(&horizontal)), (3, "acdb  ".to_string()));
+
+        let vertical = AsciiImage::concat_vertical([&tall, &AsciiImage::new(), &wide], padding);
+        assert_eq!((vertical.width(), text(&vertical)), (2, "a b cd".to_string()));
+
+        let empty = AsciiImage::concat_vertical([&AsciiImage::new(), &AsciiImage::new()], padding);
+        assert_eq!(empty.height(), 0);
+    }
+}
+
+mod transform {
+    use super::{text, text_image};
+    use crate::color::ansi_8_bit::Ansi8Bit;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+
+    #[test]
+    fn flips_mirror_glyphs() {
+        let mut image = text_image(3, "/(▌▀^▖");
+
+        image.flip_horizontal(false);
+        assert_eq!(text(&image), "▌(/▖^▀");
+        image.flip_horizontal(true);
+        assert_eq!(text(&image), "\\)▐▀^▗");
+
+        image.flip_vertical(true);
+        assert_eq!(text(&image), "▄v▝/)▐");
+    }
+
+    #[test]
+    fn rotate_180_mirrors_both_ways() {
+        let mut image = text_image(2, "/▘");
+        image.rotate_180(true);
+        assert_eq!(text(&image), "▗/");
+    }
+
+    #[test]
+    fn transpose() {
+        let image = text_image(3, "abcdef").transpose();
+        assert_eq!((image.width(), text(&image)), (2, "adbecf".to_string()));
+    }
+
+    #[test]
+    fn rotate_90_keeps_proportions() {
+        let font = Font::new_float([' ', '#'], 1.0, 0.5).unwrap();
+        let image = text_image(4, "|-▀▐|-▀▐");
+
+        let rotated = image.rotate_90(&font, true);
+        // Two rows are as tall as four columns are wide, so every other column is kept as a row.
+        assert_eq!((rotated.width(), rotated.height()), (4, 2));
+        assert_eq!(text(&rotated), "||||▄▄▄▄");
+    }
+
+    #[test]
+    fn empty_images() {
+        let font = Font::new_float([' ', '#'], 1.0, 0.5).unwrap();
+        let mut image = AsciiImage::<Ansi8Bit>::new();
+
+        image.flip_vertical(true);
+        image.flip_horizontal(true);
+        image.rotate_180(true);
+        assert_eq!(image, AsciiImage::new());
+
+        assert_eq!(image.transpose(), AsciiImage::new());
+        assert_eq!(image.rotate_90(&font, true), AsciiImage::new());
+        assert_eq!(image.rotate_270(&font, true), AsciiImage::new());
+        assert_eq!(image.resize(3, 3), AsciiImage::new());
+    }
+}
+
+mod figlet {
+    use super::{text, text_image};
+    use crate::color::ansi_8_bit::Ansi8Bit;
+    use crate::figlet::{FigletError, FigletFont, Layout, SmushingRules};
+
+    /// A font with the header `header` and a height of 1,
+    /// where the characters in `glyphs` are drawn as the given row and all other characters are empty.
+    fn font(header: &str, glyphs: &[(char, &str)]) -> Result<FigletFont, FigletError> {
+        let mut source = format!("{header}\n");
+
+        for character in ' '..='~' {
+            let glyph = glyphs.iter().find(|(glyph, _)| *glyph == character).map_or("", |(_, row)| row);
+            source += &format!("{glyph}@\n");
+        }
+
+        source.parse()
+    }
+
+    fn smush(rules: SmushingRules, left: &str, right: &str) -> String {
+        let font = font("flf2a$ 1 1 4 -1 0", &[('l', left), ('r', right)])
+            .unwrap()
+            .with_layout(Layout::Smushing(rules));
+
+        font.render_rows("lr").concat()
+    }
+
+    #[test]
+    fn header() {
+        let layout = |header| font(header, &[]).unwrap().layout();
+
+        assert_eq!(layout("flf2a$ 1 1 4 -1 0"), Layout::FullWidth);
+        assert_eq!(layout("flf2a$ 1 1 4 0 0"), Layout::Fitting);
+        assert_eq!(layout("flf2a$ 1 1 4 5 0"), Layout::Smushing(SmushingRules::from_bits(5)));
+        // The full layout takes precedence over the old layout.
+        assert_eq!(layout("flf2a$ 1 1 4 5 0 0 64"), Layout::Fitting);
+        assert_eq!(layout("flf2a$ 1 1 4 -1 0 0 160"), Layout::Smushing(SmushingRules::HARDBLANK));
+
+        let font = font("flf2a$ 1 1 4 -1 1 0\na comment", &[]).unwrap();
+        assert_eq!(font.height(), 1);
+
+        assert!(matches!("flf2 1 1 4 -1 0".parse::<FigletFont>(), Err(FigletError::InvalidHeader)));
+        assert!(matches!("flf2a$ 1 1".parse::<FigletFont>(), Err(FigletError::InvalidHeader)));
+        assert!(matches!("flf2a$ 2 1 4 -1 0\n@".parse::<FigletFont>(), Err(FigletError::UnexpectedEnd(' '))));
+    }
+
+    #[test]
+    fn code_tagged_characters() {
+        let required = "flf2a$ 1 1 4 -1 0\n".to_string() + &"@\n".repeat(102);
+
+        let font: FigletFont = (required.clone() + "0x263A smiley\n:)@\n-1 invalid\nxx@\n").parse().unwrap();
+        assert_eq!(font.render_rows("☺☺"), [":):)"]);
+
+        let invalid = required + "smiley\n:)@\n";
+        assert!(matches!(invalid.parse::<FigletFont>(), Err(FigletError::InvalidCodeTag(_))));
+    }
+
+    #[test]
+    fn fitting() {
+        let font = font("flf2a$ 1 1 4 0 0", &[('l', "x- "), ('r', " -y")]).unwrap();
+        assert_eq!(font.render_rows("lr"), ["x--y"]);
+
+        let font = font.with_layout(Layout::FullWidth);
+        assert_eq!(font.render_rows("lr"), ["x-  -y"]);
+    }
+
+    #[test]
+    fn smushing_rules() {
+        assert_eq!(smush(SmushingRules::UNIVERSAL, "xa", "by"), "xby");
+
+        assert_eq!(smush(SmushingRules::EQUAL_CHARACTER, "x-", "-y"), "x-y");
+        assert_eq!(smush(SmushingRules::EQUAL_CHARACTER, "x-", "+y"), "x-+y");
+
+        assert_eq!(smush(SmushingRules::UNDERSCORE, "x_", "/y"), "x/y");
+        assert_eq!(smush(SmushingRules::UNDERSCORE, "x(", "_y"), "x(y");
+
+        assert_eq!(smush(SmushingRules::HIERARCHY, "x|", "/y"), "x/y");
+        assert_eq!(smush(SmushingRules::HIERARCHY, "x>", "]y"), "x>y");
+        assert_eq!(smush(SmushingRules::HIERARCHY, "x/", "\\y"), "x/\\y");
+
+        assert_eq!(smush(SmushingRules::OPPOSITE_PAIR, "x]", "[y"), "x|y");
+        assert_eq!(smush(SmushingRules::OPPOSITE_PAIR, "x(", ")y"), "x|y");
+
+        assert_eq!(smush(SmushingRules::BIG_X, "x/", "\\y"), "x|y");
+        assert_eq!(smush(SmushingRules::BIG_X, "x\\", "/y"), "xYy");
+        assert_eq!(smush(SmushingRules::BIG_X, "x>", "<y"), "xXy");
+
+        // Hardblanks are drawn as spaces, but only smushed by their own rule.
+        assert_eq!(smush(SmushingRules::HARDBLANK, "x$", "$y"), "x y");
+        assert_eq!(smush(SmushingRules::EQUAL_CHARACTER, "x$", "$y"), "x  y");
+    }
+
+    #[test]
+    fn banners_are_see_through() {
+        let font = font("flf2a$ 1 1 4 -1 0", &[('i', "# ")]).unwrap();
+        let banner = font.render("ii", Ansi8Bit::from_index(9), Ansi8Bit::from_index(4));
+
+        let mut image = text_image(5, "abcde");
+        image.overlay(&banner, 1, 0, |_| false);
+
+        // The gaps between the characters keep the cells below.
+        assert_eq!(text(&image), "a#c#e");
+        assert_eq!(image.get(1, 0).unwrap().background.index(), 4);
+        assert_eq!(image.get(2, 0).unwrap().background.index(), 0);
+    }
+
+    #[test]
+    fn standard_font_matches_figlet() {
+        let rows = FigletFont::standard().render_rows("Hello, World!");
+
+        // The output of `figlet 'Hello, World!'`.
+        let expected = [
+            r" _   _      _ _         __        __         _     _ _ ",
+            r"| | | | ___| | | ___    \ \      / /__  _ __| | __| | |",
+            r"| |_| |/ _ \ | |/ _ \    \ \ /\ / / _ \| '__| |/ _` | |",
+            r"|  _  |  __/ | | (_) |    \ V  V / (_) | |  | | (_| |_|",
+            r"|_| |_|\___|_|_|\___( )    \_/\_/ \___/|_|  |_|\__,_(_)",
+            r"                    |/                                 ",
+        ];
+        assert_eq!(rows, expected);
+    }
+}
+
+mod attributes {
+    use super::cell;
+    use crate::cell::{AsciiCell, Attributes};
+    use crate::color::ansi_3_bit::Ansi3Bit;
+    use crate::color::Color;
+    use crate::conversion::Conversion;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+    use crate::renderer::{CellRenderer, GradientMatcher};
+    use image::Rgb;
+
+    fn with_attributes(character: char, attributes: Attributes) -> AsciiCell<Ansi3Bit> {
+        AsciiCell {
+            attributes,
+            ..cell(character, Ansi3Bit::White, Ansi3Bit::Black)
+        }
+    }
+
+    #[test]
+    fn only_changed_attributes_are_written() {
+        let bold_italic = Attributes {
+            italic: true,
+            ..Attributes::BOLD
+        };
+        let cells = [
+            with_attributes('a', Attributes::BOLD),
+            with_attributes('b', bold_italic),
+            with_attributes('c', Attributes::DIM),
+            with_attributes('d', Attributes::NONE),
+        ];
+        let image = AsciiImage::from_cells(cells, 4, 1).unwrap();
+
+        // Turning off bold or dim turns off both, so dim has to be turned on again.
+        assert_eq!(image.to_string(), "\x1b[1;40;37ma\x1b[3mb\x1b[22;2;23mc\x1b[22md\x1b[m");
+    }
+
+    #[test]
+    fn repeated_characters_are_written() {
+        let cells = [
+            with_attributes('a', Attributes::NONE),
+            with_attributes('a', Attributes::NONE),
+            with_attributes('b', Attributes::NONE),
+        ];
+        let image = AsciiImage::from_cells(cells, 3, 1).unwrap();
+
+        // The foreground color is only written once, but every character is.
+        assert_eq!(image.to_string(), "\x1b[40;37maab\x1b[m");
+    }
+
+    #[test]
+    fn attribute_shading() {
+        let font = Font::new_float([' ', '#'], 1.0, 0.5).unwrap();
+        let dim_red = Ansi3Bit::Red.to_rgb_with_attributes(Attributes::DIM);
+
+        let plain: AsciiCell<Ansi3Bit> = GradientMatcher.render_color(dim_red, &font, &Conversion::default());
+        assert_eq!(plain.attributes, Attributes::NONE);
+
+        let conversion = Conversion {
+            attribute_shading: true,
+            ..Conversion::default()
+        };
+        let shaded: AsciiCell<Ansi3Bit> = GradientMatcher.render_color(dim_red, &font, &conversion);
+        assert_eq!(shaded.attributes, Attributes::DIM);
+        assert_eq!(shaded.foreground.unwrap().color, Ansi3Bit::Red);
+    }
+
+    #[test]
+    fn reverse_video() {
+        // The dot covers a quarter of the cell.
+        let font = Font::new_float([' ', '.'], 0.25, 0.5).unwrap();
+        // Three quarters dim red, the rest black.
+        let color = Rgb([95, 0, 0]);
+
+        let conversion = Conversion {
+            attribute_shading: true,
+            ..Conversion::default()
+        };
+        let plain: AsciiCell<Ansi3Bit> = GradientMatcher.render_color(color, &font, &conversion);
+        assert!(!plain.attributes.reverse);
+
+        let conversion = Conversion {
+            reverse_video: true,
+            ..conversion
+        };
+        let reversed: AsciiCell<Ansi3Bit> = GradientMatcher.render_color(color, &font, &conversion);
+        // The dim red foreground fills the uncovered part of the cell, the dot is drawn in black.
+        let expected = AsciiCell {
+            attributes: Attributes {
+                reverse: true,
+                ..Attributes::DIM
+            },
+            ..cell('.', Ansi3Bit::Red, Ansi3Bit::Black)
+        };
+        assert_eq!(reversed, expected);
+    }
+
+    #[test]
+    fn reverse_video_needs_attribute_shading() {
+        let font = Font::new_float([' ', '.', '#'], 0.75, 0.5).unwrap();
+        let reverse_video = Conversion {
+            reverse_video: true,
+            ..Conversion::default()
+        };
+
+        for color in [Rgb([95, 0, 0]), Rgb([30, 140, 200]), Rgb([250, 250, 10])] {
+            let plain: AsciiCell<Ansi3Bit> = GradientMatcher.render_color(color, &font, &Conversion::default());
+            let reversed: AsciiCell<Ansi3Bit> = GradientMatcher.render_color(color, &font, &reverse_video);
+            assert_eq!(reversed, plain);
+        }
+    }
+}
+
+mod line_art {
+    use super::text;
+    use crate::color::colorless::Colorless;
+    use crate::conversion::Conversion;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+    use crate::line_art::{EdgeDetector, LineCharacters};
+    use image::{GrayImage, Luma};
+
+    /// Converts a 16 × 16 image, which is white where `is_white` and black elsewhere, to 4 × 4 cells.
+    /// Cells without lines are blank.
+    fn lines(
+        detector: EdgeDetector,
+        characters: LineCharacters,
+        is_white: impl Fn(u32, u32) -> bool,
+    ) -> String {
+        let image = GrayImage::from_fn(16, 16, |x, y| Luma([if is_white(x, y) { 255 } else { 0 }]));
+        let font = Font::new_float([' '], 1.0, 1.0).unwrap();
+        let conversion = Conversion {
+            line_art: Some(detector),
+            line_characters: characters,
+            ..Conversion::default()
+        };
+
+        text(&AsciiImage::<Colorless>::from_image_with_conversion(&image, &font, &conversion, 4, 4))
+    }
+
+    #[test]
+    fn vertical_edge() {
+        let sobel = lines(EdgeDetector::default(), LineCharacters::ASCII, |x, _| 8 <= x);
+        assert_eq!(sobel, " || ".repeat(4));
+
+        let box_drawing = lines(EdgeDetector::default(), LineCharacters::BOX_DRAWING, |x, _| 8 <= x);
+        assert_eq!(box_drawing, " ││ ".repeat(4));
+    }
+
+    #[test]
+    fn horizontal_edge() {
+        // The edge lies along the bottom of the cells above it.
+        let sobel = lines(EdgeDetector::default(), LineCharacters::ASCII, |_, y| 8 <= y);
+        assert_eq!(sobel, ["    ", "____", "----", "    "].concat());
+    }
+
+    #[test]
+    fn diagonal_edges() {
+        let falling = lines(EdgeDetector::default(), LineCharacters::ASCII, |x, y| y < x);
+        assert_eq!(falling, ["\\   ", " \\  ", "  \\ ", "   \\"].concat());
+
+        let rising = lines(EdgeDetector::default(), LineCharacters::ASCII, |x, y| x + y < 15);
+        assert_eq!(rising, ["   /", "  / ", " /  ", "/   "].concat());
+    }
+
+    #[test]
+    fn canny_thins_edges() {
+        let canny = EdgeDetector::Canny { low: 0.1, high: 0.3 };
+
+        // The blurred edge is thinned to a single column of pixels, so it only falls in one cell.
+        let vertical = lines(canny, LineCharacters::ASCII, |x, _| 8 <= x);
+        assert_eq!(vertical.matches('|').count(), 4);
+        assert!(vertical.chars().all(|character| character == '|' || character == ' '));
+
+        // Without any edges nothing is drawn.
+        assert_eq!(lines(canny, LineCharacters::ASCII, |_, _| true), " ".repeat(16));
+    }
+}
+
+mod preprocessing {
+    use crate::preprocessing::{Preprocessing, UnsharpMask};
+    use image::{GenericImageView, GrayImage, Luma};
+
+    #[test]
+    fn unsharp_mask_increases_edge_contrast() {
+        let edge = GrayImage::from_fn(16, 4, |x, _| Luma([if x < 8 { 80 } else { 170 }]));
+        let luma = |preprocessing: &Preprocessing, x| preprocessing.apply(&edge).get_pixel(x, 1).0[0];
+
+        assert_eq!((luma(&Preprocessing::NONE, 7), luma(&Preprocessing::NONE, 8)), (80, 170));
+
+        let sharpened = Preprocessing {
+            unsharp_mask: Some(UnsharpMask {
+                sigma: 2.0,
+                threshold: 0.0,
+            }),
+            ..Preprocessing::NONE
+        };
+        assert!(luma(&sharpened, 7) < 80);
+        assert!(170 < luma(&sharpened, 8));
+        // Flat regions far from the edge are left as they are.
+        assert_eq!(luma(&sharpened, 0), 80);
+
+        let thresholded = Preprocessing {
+            unsharp_mask: Some(UnsharpMask {
+                sigma: 2.0,
+                threshold: 0.5,
+            }),
+            ..Preprocessing::NONE
+        };
+        assert_eq!(luma(&thresholded, 7), 80);
+    }
+
+    #[test]
+    fn empty_image() {
+        let sharpened = Preprocessing {
+            unsharp_mask: Some(UnsharpMask {
+                sigma: 2.0,
+                threshold: 0.0,
+            }),
+            ..Preprocessing::NONE
+        };
+        assert_eq!(sharpened.apply(&GrayImage::new(0, 0)).dimensions(), (0, 0));
+    }
+}
+
+mod filter {
+    use crate::color::ansi_24_bit::Ansi24Bit;
+    use crate::color::Color;
+    use crate::conversion::Conversion;
+    use crate::filter::Filter;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+    use image::{GrayImage, Luma};
+
+    const FILTERS: [Filter; 6] = [
+        Filter::Box,
+        Filter::Area,
+        Filter::Center,
+        Filter::Gaussian,
+        Filter::Lanczos,
+        Filter::Median,
+    ];
+
+    /// The luma of every cell when the row of pixels is converted to `width` cells.
+    fn sample(filter: Filter, pixels: &[u8], width: u32) -> Vec<u8> {
+        let image = GrayImage::from_fn(pixels.len() as u32, 1, |x, _| Luma([pixels[x as usize]]));
+        let font = Font::new_float([' ', '#'], 1.0, 1.0).unwrap();
+        let conversion = Conversion {
+            filter,
+            ..Conversion::default()
+        };
+
+        AsciiImage::<Ansi24Bit>::from_image_with_conversion(&image, &font, &conversion, width, 1)
+            .cells()
+            .iter()
+            .map(|cell| cell.background.to_rgb().0[0])
+            .collect()
+    }
+
+    #[test]
+    fn flat_images_are_kept() {
+        for filter in FILTERS {
+            assert_eq!(sample(filter, &[70; 9], 3), [70; 3], "{filter:?}");
+        }
+    }
+
+    #[test]
+    fn averages() {
+        let pixels = [0, 90, 255];
+
+        assert_eq!(sample(Filter::Box, &pixels, 1), [115]);
+        assert_eq!(sample(Filter::Area, &pixels, 1), [115]);
+        assert_eq!(sample(Filter::Center, &pixels, 1), [90]);
+        assert_eq!(sample(Filter::Median, &pixels, 1), [90]);
+    }
+
+    #[test]
+    fn partially_covered_pixels() {
+        // Two cells split the middle pixel, which only the area filter weighs by coverage.
+        let pixels = [0, 0, 200];
+
+        assert_eq!(sample(Filter::Box, &pixels, 2), [0, 100]);
+        assert_eq!(sample(Filter::Area, &pixels, 2), [0, 133]);
+    }
+
+    #[test]
+    fn median_ignores_outliers() {
+        let pixels = [10, 10, 10, 10, 255];
+
+        assert_eq!(sample(Filter::Median, &pixels, 1), [10]);
+        assert_eq!(sample(Filter::Box, &pixels, 1), [59]);
+    }
+
+    #[test]
+    fn lanczos_overshoots_edges() {
+        let pixels: Vec<u8> = [[50; 8], [200; 8]].concat();
+
+        let gaussian = sample(Filter::Gaussian, &pixels, 8);
+        let lanczos = sample(Filter::Lanczos, &pixels, 8);
+
+        // The Gaussian blurs the edge, the Lanczos kernel rings on either side of it.
+        assert!(gaussian.iter().all(|luma| (50..=200).contains(luma)), "{gaussian:?}");
+        assert!(lanczos.iter().any(|&luma| luma < 50) && lanczos.iter().any(|&luma| 200 < luma), "{lanczos:?}");
+    }
+
+    #[test]
+    fn empty_images_are_black() {
+        for filter in FILTERS {
+            assert_eq!(sample(filter, &[], 2), [0; 2], "{filter:?}");
+        }
+    }
+}
+
+mod linear_light {
+    use crate::cell::AsciiCell;
+    use crate::color::ansi_24_bit::Ansi24Bit;
+    use crate::color::ansi_4_bit::Ansi4Bit;
+    use crate::color::util::{decode, encode};
+    use crate::color::Color;
+    use crate::conversion::Conversion;
+    use crate::filter::Filter;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+    use image::{GrayImage, Luma, Rgb};
+    use num_rational::Ratio;
+    use num_traits::ToPrimitive;
+
+    /// The color of a single cell converted from a black and white checkerboard.
+    fn checkerboard(filter: Filter, linear_light: bool) -> Rgb<u8> {
+        let image = GrayImage::from_fn(4, 4, |x, y| Luma([if (x + y) % 2 == 0 { 0 } else { 255 }]));
+        let font = Font::new_float([' ', '#'], 1.0, 1.0).unwrap();
+        let conversion = Conversion {
+            filter,
+            linear_light,
+            ..Conversion::default()
+        };
+
+        let converted = AsciiImage::<Ansi24Bit>::from_image_with_conversion(&image, &font, &conversion, 1, 1);
+        converted.cells()[0].background.to_rgb()
+    }
+
+    #[test]
+    fn averages_in_linear_light() {
+        // Half of the light of white is encoded as 188 in sRGB.
+        assert_eq!(checkerboard(Filter::Box, false), Rgb([127; 3]));
+        assert_eq!(checkerboard(Filter::Box, true), Rgb([188; 3]));
+        assert_eq!(checkerboard(Filter::Area, true), Rgb([188; 3]));
+    }
+
+    /// The color of the cell as the screen shows it, with the light of the character
+    /// and of the background mixed according to the coverage of the character.
+    fn displayed<G: AsRef<[char]>>(cell: &AsciiCell<Ansi4Bit>, font: &Font<G>) -> Rgb<u8> {
+        let background = cell.background.to_rgb();
+        let (foreground, coverage) = cell.foreground.map_or((background, Ratio::ZERO), |foreground| {
+            let index = font.gradient().iter().position(|&character| character == foreground.character);
+            (foreground.color.to_rgb(), font.coverage(index.unwrap()))
+        });
+        let coverage = coverage.to_f64().unwrap();
+
+        Rgb(std::array::from_fn(|channel| {
+            let background = decode(background.0[channel]) as f64;
+            let foreground = decode(foreground.0[channel]) as f64;
+            encode(((1.0 - coverage) * background + coverage * foreground).round() as u16)
+        }))
+    }
+
+    /// The color in CIELAB, in which distances roughly match perceived differences.
+    fn lab(Rgb(color): Rgb<u8>) -> [f64; 3] {
+        let [r, g, b] = color.map(|channel| decode(channel) as f64 / u16::MAX as f64);
+
+        // Relative to the D65 white point.
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.9505;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.089;
+
+        let f = |t: f64| if t > 216.0 / 24389.0 { t.cbrt() } else { t * 841.0 / 108.0 + 4.0 / 29.0 };
+        [116.0 * f(y) - 16.0, 500.0 * (f(x) - f(y)), 200.0 * (f(y) - f(z))]
+    }
+
+    #[test]
+    fn mona_lisa_is_closer_in_linear_light() {
+        let source = image::load_from_memory(include_bytes!("../benches/Mona Lisa.jpg")).unwrap();
+        let font = Font::new_float([' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'], 0.3, 0.5).unwrap();
+        let (width, height) = (40, 30);
+
+        let conversion = |linear_light| Conversion {
+            linear_light,
+            ..Conversion::default()
+        };
+
+        // What the eye sees from afar: the light of the pixels under each cell, averaged.
+        let target =
+            AsciiImage::<Ansi24Bit>::from_image_with_conversion(&source, &font, &conversion(true), width, height);
+
+        // The mean perceptual distance between the target and the displayed cells, in ΔE*ab.
+        let error = |linear_light| {
+            let conversion = conversion(linear_light);
+            let converted =
+                AsciiImage::<Ansi4Bit>::from_image_with_conversion(&source, &font, &conversion, width, height);
+
+            let total: f64 = converted
+                .cells()
+                .iter()
+                .zip(target.cells())
+                .map(|(cell, target)| {
+                    let [l, a, b] = lab(displayed(cell, &font));
+                    let [target_l, target_a, target_b] = lab(target.background.to_rgb());
+                    ((l - target_l).powi(2) + (a - target_a).powi(2) + (b - target_b).powi(2)).sqrt()
+                })
+                .sum();
+
+            total / (width * height) as f64
+        };
+
+        // About 22 when matching in sRGB and 11.4 in linear light.
+        let (srgb, linear) = (error(false), error(true));
+        assert!(linear < srgb * 0.6, "{linear} is not much less than {srgb}");
+    }
+}
+
+mod sizing {
+    use super::{blank, text};
+    use crate::color::colorless::Colorless;
+    use crate::conversion::Conversion;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+    use crate::sizing::{Gravity, Sizing};
+    use image::{GrayImage, Luma};
+
+    fn sizings() -> [Sizing<Colorless>; 4] {
+        [
+            Sizing::Fit,
+            Sizing::Fill(Gravity::Center),
+            Sizing::Stretch,
+            Sizing::Letterbox {
+                padding: blank(Colorless),
+                gravity: Gravity::Center,
+            },
+        ]
+    }
+
+    fn size(image: &GrayImage, sizing: &Sizing<Colorless>, width: u32, height: u32) -> (u32, u32) {
+        let font = Font::new_float([' ', '#'], 1.0, 1.0).unwrap();
+        let ascii = AsciiImage::from_image_with_sizing(image, &font, &Conversion::default(), sizing, width, height);
+        (ascii.width(), ascii.height())
+    }
+
+    #[test]
+    fn boxes() {
+        let image = GrayImage::new(8, 4);
+        let [fit, fill, stretch, letterbox] = sizings();
+
+        assert_eq!(size(&image, &fit, 10, 10), (10, 5));
+        assert_eq!(size(&image, &fill, 10, 10), (10, 10));
+        assert_eq!(size(&image, &stretch, 10, 10), (10, 10));
+        assert_eq!(size(&image, &letterbox, 10, 10), (10, 10));
+    }
+
+    #[test]
+    fn empty_sources() {
+        let image = GrayImage::new(0, 0);
+        let [fit, fill, stretch, letterbox] = sizings();
+
+        assert_eq!(size(&image, &fit, 4, 3), (0, 0));
+        assert_eq!(size(&image, &fill, 4, 3), (0, 0));
+        assert_eq!(size(&image, &stretch, 4, 3), (0, 0));
+        // Only the padding is left.
+        assert_eq!(size(&image, &letterbox, 4, 3), (4, 3));
+    }
+
+    #[test]
+    fn empty_boxes() {
+        let image = GrayImage::new(8, 4);
+
+        for sizing in sizings() {
+            for (width, height) in [(0, 0), (0, 3), (4, 0)] {
+                let (_, scaled_height) = size(&image, &sizing, width, height);
+                assert_eq!(scaled_height, 0, "{sizing:?} in {width}x{height}");
+            }
+        }
+    }
+
+    #[test]
+    fn regions_are_clipped() {
+        let font = Font::new_float([' ', '#'], 1.0, 1.0).unwrap();
+        let conversion = Conversion::default();
+        let image = GrayImage::from_fn(4, 4, |x, _| Luma([if x < 2 { 0 } else { 255 }]));
+
+        let region = |position, size| {
+            let ascii = AsciiImage::<Colorless>::from_image_region(&image, &font, &conversion, position, size, 2, 1);
+            text(&ascii)
+        };
+
+        assert_eq!(region((1, 0), (2, 4)), " #");
+        assert_eq!(region((2, 2), (8, 8)), "##");
+        assert_eq!(region((6, 0), (2, 2)), "  ");
+    }
+
+    #[test]
+    fn empty_images_keep_aspect_ratio() {
+        let font = Font::new_float([' ', '#'], 1.0, 0.5).unwrap();
+
+        for image in [GrayImage::new(0, 0), GrayImage::new(0, 4), GrayImage::new(4, 0)] {
+            assert!(AsciiImage::<Colorless>::from_image_with_width(&image, &font, 10).cells().is_empty());
+            assert!(AsciiImage::<Colorless>::from_image_with_height(&image, &font, 10).cells().is_empty());
+            assert!(AsciiImage::<Colorless>::from_image(&image, &font).cells().is_empty());
+        }
+    }
+}
+
+mod renderer {
+    use super::{blank, cell, text};
+    use crate::cell::{AsciiCell, Attributes, Foreground};
+    use crate::color::colorless::Colorless;
+    use crate::color::{default_from_rgb, default_new_cell, Color};
+    use crate::conversion::Conversion;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+    use crate::renderer::{CellRenderer, GradientMatcher};
+    use crate::sizing::Sizing;
+    use image::{GrayImage, Luma, Rgb};
+    use std::io::Write;
+
+    /// A palette defined outside of the crate.
+    #[derive(Copy, Clone, PartialEq, Debug, Default)]
+    struct Gray(u8);
+
+    const GRAYS: [Gray; 4] = [Gray(0), Gray(85), Gray(170), Gray(255)];
+
+    impl Color for Gray {
+        fn to_rgb(&self) -> Rgb<u8> {
+            Rgb([self.0; 3])
+        }
+
+        fn from_rgb(color: Rgb<u8>) -> Self {
+            default_from_rgb(&GRAYS, color)
+        }
+
+        fn write_background(&self, mut to: impl Write) -> std::io::Result<()> {
+            write!(to, "48;2;{0};{0};{0}", self.0)
+        }
+
+        fn write_foreground(&self, mut to: impl Write) -> std::io::Result<()> {
+            write!(to, "38;2;{0};{0};{0}", self.0)
+        }
+    }
+
+    impl CellRenderer<Gray> for GradientMatcher {
+        fn render_color<G: AsRef<[char]>>(
+            &self,
+            color: Rgb<u8>,
+            font: &Font<G>,
+            conversion: &Conversion,
+        ) -> AsciiCell<Gray> {
+            default_new_cell(&GRAYS, color, font, conversion)
+        }
+    }
+
+    /// Draws every cell as `#`, whatever its color.
+    pub(super) struct Hashes;
+
+    impl CellRenderer<Colorless> for Hashes {
+        fn render_color<G: AsRef<[char]>>(
+            &self,
+            _color: Rgb<u8>,
+            _font: &Font<G>,
+            _conversion: &Conversion,
+        ) -> AsciiCell<Colorless> {
+            AsciiCell {
+                background: Colorless,
+                foreground: Some(Foreground {
+                    color: Colorless,
+                    character: '#',
+                }),
+                attributes: Attributes::NONE,
+            }
+        }
+    }
+
+    #[test]
+    fn palettes_from_other_crates() {
+        let font = Font::new_float([' ', '#'], 1.0, 1.0).unwrap();
+        let image = GrayImage::from_fn(2, 1, |x, _| Luma([[0, 170][x as usize]]));
+
+        let ascii = AsciiImage::<Gray>::from_image_with_dimensions(&image, &font, 2, 1);
+
+        assert_eq!(Gray::from_rgb(Rgb([90, 80, 100])), Gray(85));
+        assert_eq!(ascii.cells(), [blank(Gray(0)), cell('#', Gray(170), Gray(0))]);
+    }
+
+    #[test]
+    fn renderers_are_passed_on() {
+        let font = Font::new_float([' ', '#'], 1.0, 1.0).unwrap();
+        let conversion = Conversion::default();
+        let image = GrayImage::new(4, 4);
+
+        let sized =
+            AsciiImage::from_image_with_sizing_and_renderer(&image, &font, &conversion, &Hashes, &Sizing::Fit, 2, 2);
+        let region =
+            AsciiImage::from_image_region_with_renderer(&image, &font, &conversion, &Hashes, (1, 1), (2, 2), 2, 1);
+
+        assert_eq!(text(&sized), "####");
+        assert_eq!(text(&region), "##");
+    }
+}
+
+mod serial {
+    use crate::cell::AsciiCell;
+    use crate::color::ansi_4_bit::Ansi4Bit;
+    use crate::color::{default_new_cell, Color};
+    use crate::conversion::Conversion;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+    use crate::renderer::{CellRenderer, GradientMatcher};
+    use image::{Rgb, RgbImage};
+    use std::io::Write;
+    use std::marker::PhantomData;
+
+    /// A color that can't be sent to other threads.
+    #[derive(Copy, Clone, Default)]
+    struct Local(PhantomData<*const ()>);
+
+    impl Color for Local {
+        fn to_rgb(&self) -> Rgb<u8> {
+            Rgb([0; 3])
+        }
+
+        fn from_rgb(_: Rgb<u8>) -> Self {
+            Local::default()
+        }
+
+        fn write_background(&self, _: impl Write) -> std::io::Result<()> {
+            Ok(())
+        }
+
+        fn write_foreground(&self, _: impl Write) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl CellRenderer<Local> for GradientMatcher {
+        fn render_color<G: AsRef<[char]>>(
+            &self,
+            color: Rgb<u8>,
+            font: &Font<G>,
+            conversion: &Conversion,
+        ) -> AsciiCell<Local> {
+            default_new_cell(&[Local::default()], color, font, conversion)
+        }
+    }
+
+    #[test]
+    fn serial_conversions_match() {
+        let image = RgbImage::from_fn(12, 8, |x, y| Rgb([x as u8 * 20, y as u8 * 30, 100]));
+        let font = Font::new_float(vec![' ', '.', ':', '#'], 1.0, 0.5).unwrap();
+
+        assert_eq!(
+            AsciiImage::<Ansi4Bit>::from_image_serial(&image, &font),
+            AsciiImage::from_image(&image, &font),
+        );
+        assert_eq!(
+            AsciiImage::<Ansi4Bit>::from_image_with_width_serial(&image, &font, 5),
+            AsciiImage::from_image_with_width(&image, &font, 5),
+        );
+        assert_eq!(
+            AsciiImage::<Ansi4Bit>::from_image_with_height_serial(&image, &font, 3),
+            AsciiImage::from_image_with_height(&image, &font, 3),
+        );
+    }
+
+    #[test]
+    fn colors_that_are_not_send() {
+        let image = RgbImage::new(4, 4);
+        let font = Font::new_float(vec![' ', '#'], 1.0, 1.0).unwrap();
+
+        let ascii = AsciiImage::<Local>::from_image_serial(&image, &font);
+        assert_eq!(ascii.cells().len(), 16);
+
+        // Without rayon nothing needs to be sent between threads.
+        #[cfg(not(feature = "rayon"))]
+        assert_eq!(AsciiImage::<Local>::from_image(&image, &font).cells().len(), 16);
+    }
+}
+
+mod packed {
+    use super::{blank, cell, text_image};
+    use crate::cell::{AsciiCell, Attributes};
+    use crate::color::ansi_24_bit::Ansi24Bit;
+    use crate::color::ansi_8_bit::Ansi8Bit;
+    use crate::color::compact::CompactColor;
+    use crate::color::mirc::{self, Mirc};
+    use crate::color::variants::{ANSI_3_BIT, ANSI_4_BIT, ANSI_8_BIT, CUBE, GRAYSCALE};
+    use crate::image::AsciiImage;
+    use crate::packed::PackedCell;
+    use std::fmt::Debug;
+
+    fn assert_round_trip<C: CompactColor + PartialEq + Debug>(colors: impl IntoIterator<Item = C>) {
+        for color in colors {
+            assert_eq!(C::expand(color.compact()), color);
+        }
+    }
+
+    #[test]
+    fn compact_colors_round_trip() {
+        assert_round_trip(ANSI_3_BIT);
+        assert_round_trip(ANSI_4_BIT);
+        assert_round_trip(ANSI_8_BIT);
+        assert_round_trip(CUBE);
+        assert_round_trip(GRAYSCALE);
+        assert_round_trip((0..mirc::COUNT).filter_map(Mirc::from_code));
+        assert_round_trip([Ansi24Bit::new(0, 0, 0), Ansi24Bit::new(255, 128, 7)]);
+    }
+
+    #[test]
+    fn image_round_trip() {
+        let mut image = text_image(3, "a b#c ");
+        let bold = AsciiCell {
+            attributes: Attributes::BOLD,
+            ..cell('%', Ansi8Bit::from_index(17), Ansi8Bit::from_index(231))
+        };
+        let reversed_space = AsciiCell {
+            attributes: Attributes {
+                reverse: true,
+                ..Attributes::NONE
+            },
+            ..cell(' ', Ansi8Bit::from_index(9), Ansi8Bit::from_index(0))
+        };
+        image.set(1, 0, blank(Ansi8Bit::from_index(200))).unwrap();
+        image.set(2, 1, bold).unwrap();
+        image.set(0, 1, reversed_space).unwrap();
+
+        let packed = image.pack();
+
+        assert_eq!((packed.width(), packed.height()), (3, 2));
+        assert_eq!(packed.unpack(), image);
+        assert_eq!(packed.get(2, 1), image.get(2, 1).cloned());
+        assert_eq!(packed.get(3, 0), None);
+        assert_eq!(packed.to_string(), image.to_string());
+    }
+
+    #[test]
+    fn packed_cells_are_smaller() {
+        // Unpacked, an 8 bit cell takes 20 bytes and a 24 bit one 16.
+        assert_eq!(size_of::<PackedCell<u8>>(), 8);
+        assert!(size_of::<PackedCell<u8>>() * 2 < size_of::<AsciiCell<Ansi8Bit>>());
+        assert_eq!(size_of::<PackedCell<[u8; 3]>>(), 12);
+        assert!(size_of::<PackedCell<[u8; 3]>>() < size_of::<AsciiCell<Ansi24Bit>>());
+    }
+
+    #[test]
+    fn empty_image() {
+        let packed = AsciiImage::<Ansi24Bit>::new().pack();
+
+        assert_eq!(packed.height(), 0);
+        assert_eq!(packed.unpack(), AsciiImage::new());
+        assert_eq!(packed.to_string(), "");
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use super::two_rows;
+    use crate::color::ansi_24_bit::Ansi24Bit;
+    use crate::color::ansi_8_bit::Ansi8Bit;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+
+    #[test]
+    fn ansi_8_bit_is_index() {
+        let json = serde_json::to_string(&Ansi8Bit::from_index(196)).unwrap();
+        assert_eq!(json, "196");
+        assert_eq!(serde_json::from_str::<Ansi8Bit>(&json).unwrap().index(), 196);
+    }
+
+    #[test]
+    fn ansi_24_bit_is_hex() {
+        let json = serde_json::to_string(&Ansi24Bit::new(255, 128, 0)).unwrap();
+        assert_eq!(json, "\"#ff8000\"");
+        assert_eq!(serde_json::from_str::<Ansi24Bit>(&json).unwrap(), Ansi24Bit::new(255, 128, 0));
+        assert!(serde_json::from_str::<Ansi24Bit>("\"ff8000\"").is_err());
+    }
+
+    #[test]
+    fn image_round_trip() {
+        let original = two_rows(2, Ansi8Bit::from_index(250), Ansi8Bit::from_index(17));
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(serde_json::from_str::<AsciiImage<Ansi8Bit>>(&json).unwrap(), original);
+
+        let original = two_rows(2, Ansi24Bit::new(4, 5, 6), Ansi24Bit::new(1, 2, 3));
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(serde_json::from_str::<AsciiImage<Ansi24Bit>>(&json).unwrap(), original);
+    }
+
+    #[test]
+    fn image_with_partial_row_is_rejected() {
+        let json = r#"{"width":2,"cells":[{"background":0,"foreground":null,"attributes":{}}]}"#;
+        assert!(serde_json::from_str::<AsciiImage<Ansi8Bit>>(json).is_err());
+    }
+
+    #[test]
+    fn font_round_trip() {
+        let original = Font::new_float(vec![' ', '.', '#'], 0.5, 0.5).unwrap();
+        let json = serde_json::to_string(&original).unwrap();
+        assert_eq!(serde_json::from_str::<Font<Vec<char>>>(&json).unwrap(), original);
+
+        let empty = r#"{"gradient":[],"max_coverage":[1,2],"aspect_ratio":[1,2]}"#;
+        assert!(serde_json::from_str::<Font<Vec<char>>>(empty).is_err());
+    }
+}
+
+mod file {
+    use super::two_rows;
+    use crate::color::ansi_24_bit::Ansi24Bit;
+    use crate::color::ansi_8_bit::Ansi8Bit;
+    use crate::file::FileError;
+    use crate::image::AsciiImage;
+
+    #[test]
+    fn round_trip() {
+        let original = two_rows(3, Ansi8Bit::from_index(250), Ansi8Bit::from_index(17));
+        let bytes = original.to_aeski_bytes();
+        assert_eq!(AsciiImage::<Ansi8Bit>::from_aeski_bytes(&bytes).unwrap(), original);
+
+        let original = two_rows(3, Ansi24Bit::new(4, 5, 6), Ansi24Bit::new(1, 2, 3));
+        let bytes = original.to_aeski_bytes();
+        assert_eq!(AsciiImage::<Ansi24Bit>::from_aeski_bytes(&bytes).unwrap(), original);
+    }
+
+    #[test]
+    fn empty_image_round_trip() {
+        let bytes = AsciiImage::<Ansi8Bit>::new().to_aeski_bytes();
+        assert_eq!(AsciiImage::<Ansi8Bit>::from_aeski_bytes(&bytes).unwrap(), AsciiImage::new());
+    }
+
+    #[test]
+    fn runs_are_compressed() {
+        let small = two_rows(2, Ansi8Bit::from_index(1), Ansi8Bit::from_index(0)).to_aeski_bytes();
+        let large = two_rows(1000, Ansi8Bit::from_index(1), Ansi8Bit::from_index(0)).to_aeski_bytes();
+
+        // Longer runs only need a couple more bytes for their counts.
+        assert!(large.len() <= small.len() + 2);
+    }
+
+    #[test]
+    fn invalid_files_are_rejected() {
+        let bytes = two_rows(2, Ansi8Bit::from_index(1), Ansi8Bit::from_index(0)).to_aeski_bytes();
+
+        assert!(matches!(
+            AsciiImage::<Ansi8Bit>::from_aeski_bytes(b"not aeski"),
+            Err(FileError::NotAeski)
+        ));
+        assert!(matches!(
+            AsciiImage::<Ansi24Bit>::from_aeski_bytes(&bytes),
+            Err(FileError::WrongPalette { .. })
+        ));
+        assert!(matches!(
+            AsciiImage::<Ansi8Bit>::from_aeski_bytes(&bytes[..bytes.len() - 1]),
+            Err(FileError::Corrupt)
+        ));
+
+        let mut newer = bytes.clone();
+        newer[5] += 1;
+        assert!(matches!(
+            AsciiImage::<Ansi8Bit>::from_aeski_bytes(&newer),
+            Err(FileError::UnsupportedVersion(_))
+        ));
+
+        // A run of no cells in front of the first run, which is a blank cell of two bytes.
+        let header = 15;
+        let mut empty_run = bytes[..header].to_vec();
+        empty_run.push(0);
+        empty_run.extend(&bytes[header + 1..header + 3]);
+        empty_run.extend(&bytes[header..]);
+        assert!(matches!(
+            AsciiImage::<Ansi8Bit>::from_aeski_bytes(&empty_run),
+            Err(FileError::Corrupt)
+        ));
+    }
+
+    #[test]
+    fn huge_images_are_rejected() {
+        let header = two_rows(2, Ansi8Bit::from_index(1), Ansi8Bit::from_index(0)).to_aeski_bytes()[..7].to_vec();
+
+        // The largest image possible, with a single run of 2^62 blank cells.
+        let mut bytes = header;
+        bytes.extend(u32::MAX.to_le_bytes());
+        bytes.extend(u32::MAX.to_le_bytes());
+        bytes.extend([0x80; 8]);
+        bytes.push(0x40);
+        bytes.extend([0, 0]);
+
+        assert!(matches!(
+            AsciiImage::<Ansi8Bit>::from_aeski_bytes(&bytes),
+            Err(FileError::Corrupt)
+        ));
+    }
+}
+
+mod ansi_art {
+    use super::{blank, cell};
+    use crate::ansi_art::{AnsiArtError, Sauce};
+    use crate::cell::{AsciiCell, Attributes, Foreground};
+    use crate::color::ansi_24_bit::Ansi24Bit;
+    use crate::color::ansi_3_bit::Ansi3Bit;
+    use crate::color::ansi_4_bit::Ansi4Bit;
+    use crate::image::AsciiImage;
+
+    fn image() -> AsciiImage<Ansi4Bit> {
+        let blue = blank(Ansi4Bit::new_bright(Ansi3Bit::Blue));
+        let cells = [
+            cell('░', Ansi4Bit::new_bright(Ansi3Bit::Red), Ansi4Bit::new_non_bright(Ansi3Bit::Cyan)),
+            blue,
+            cell('A', Ansi4Bit::new_non_bright(Ansi3Bit::Yellow), Ansi4Bit::new_bright(Ansi3Bit::White)),
+            cell('╬', Ansi4Bit::new_bright(Ansi3Bit::Black), Ansi4Bit::new_non_bright(Ansi3Bit::Black)),
+            blue,
+            blue,
+        ];
+
+        AsciiImage::from_cells(cells, 3, 2).unwrap()
+    }
+
+    fn sauce() -> Sauce {
+        Sauce {
+            title: "Mona Lisa".to_string(),
+            author: "Leonardo".to_string(),
+            date: "15030101".to_string(),
+            ice_colors: true,
+            ..Sauce::default()
+        }
+    }
+
+    #[test]
+    fn ans_round_trip() {
+        let bytes = image().to_ans(&sauce());
+        assert_eq!(Sauce::read(&bytes), Some(sauce()));
+        assert_eq!(AsciiImage::from_ans(&bytes), image());
+    }
+
+    #[test]
+    fn empty_image_round_trip() {
+        let empty = AsciiImage::<Ansi4Bit>::new();
+
+        let bytes = empty.to_ans(&sauce());
+        assert_eq!(Sauce::read(&bytes), Some(sauce()));
+        assert_eq!(AsciiImage::from_ans(&bytes), empty);
+
+        let bytes = empty.to_xbin(&sauce()).unwrap();
+        assert_eq!(AsciiImage::from_xbin(&bytes).unwrap(), empty);
+    }
+
+    #[test]
+    fn ans_without_ice_colors_darkens_backgrounds() {
+        let sauce = Sauce {
+            ice_colors: false,
+            ..sauce()
+        };
+        let read = AsciiImage::from_ans(&image().to_ans(&sauce));
+        assert!(read.cells().iter().all(|cell| !cell.background.is_bright));
+    }
+
+    #[test]
+    fn xbin_round_trip() {
+        let bytes = image().to_xbin(&sauce()).unwrap();
+        assert_eq!(AsciiImage::from_xbin(&bytes).unwrap(), image());
+
+        assert_eq!(AsciiImage::from_xbin(b"XBIN"), Err(AnsiArtError::NotXBin));
+        assert_eq!(AsciiImage::from_xbin(&bytes[..20]), Err(AnsiArtError::Truncated));
+    }
+
+    #[test]
+    fn huge_xbin_is_truncated() {
+        // The largest image possible, without any cells.
+        let bytes = [b"XBIN\x1A".as_slice(), &[0xFF, 0xFF, 0xFF, 0xFF, 16, 0]].concat();
+        assert_eq!(AsciiImage::from_xbin(&bytes), Err(AnsiArtError::Truncated));
+    }
+
+    #[test]
+    fn compressed_xbin() {
+        // A 3x1 image of a repeated red on blue `#`, stored as a single run.
+        let bytes = [b"XBIN\x1A".as_slice(), &[3, 0, 1, 0, 16, 0b1100], &[0b1100_0010, b'#', 0x14]].concat();
+        let image = AsciiImage::from_xbin(&bytes).unwrap();
+
+        let cell = AsciiCell {
+            background: Ansi4Bit::new_non_bright(Ansi3Bit::Blue),
+            foreground: Some(Foreground {
+                color: Ansi4Bit::new_non_bright(Ansi3Bit::Red),
+                character: '#',
+            }),
+            attributes: Attributes::NONE,
+        };
+        assert_eq!(image, AsciiImage::filled(3, 1, cell));
+    }
+
+    #[test]
+    fn other_palettes_are_approximated() {
+        let cell = AsciiCell {
+            background: Ansi24Bit::new(250, 0, 0),
+            foreground: Some(Foreground {
+                color: Ansi24Bit::new(0, 0, 120),
+                character: 'é',
+            }),
+            attributes: Attributes::NONE,
+        };
+        let bytes = AsciiImage::filled(1, 1, cell).to_ans(&sauce());
+        let read = AsciiImage::from_ans(&bytes);
+
+        let expected = AsciiCell {
+            background: Ansi4Bit::new_bright(Ansi3Bit::Red),
+            foreground: Some(Foreground {
+                color: Ansi4Bit::new_non_bright(Ansi3Bit::Blue),
+                character: 'é',
+            }),
+            attributes: Attributes::NONE,
+        };
+        assert_eq!(read, AsciiImage::filled(1, 1, expected));
+    }
+}
+
+mod cp437 {
+    use super::text;
+    use crate::color::colorless::Colorless;
+    use crate::cp437::{
+        decode, encode, encode_printable, encode_str, HALF_BLOCKS, HALF_BLOCKS_FONT, SHADES, SHADES_FONT,
+    };
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+    use image::{GrayImage, Luma};
+    use num_rational::Ratio;
+
+    #[test]
+    fn round_trip() {
+        for byte in 1..=u8::MAX {
+            assert_eq!(encode(decode(byte)), Some(byte));
+        }
+        assert_eq!(encode('€'), None);
+    }
+
+    #[test]
+    fn gradients_are_encodable() {
+        assert!(SHADES.iter().chain(&HALF_BLOCKS).all(|&character| encode(character).is_some()));
+    }
+
+    #[test]
+    fn fonts() {
+        assert_eq!(Font::new(SHADES, Ratio::from_integer(1), Ratio::new(1, 2)), Ok(SHADES_FONT));
+        assert_eq!(Font::new(HALF_BLOCKS, Ratio::from_integer(1), Ratio::new(1, 2)), Ok(HALF_BLOCKS_FONT));
+        assert_eq!(SHADES_FONT.coverage(1), Ratio::new(1, 4));
+
+        let image = GrayImage::from_fn(5, 2, |x, _| Luma([(x * 255 / 4) as u8]));
+        let ascii = AsciiImage::<Colorless>::from_image_with_dimensions(&image, &SHADES_FONT, 5, 1);
+        assert_eq!(text(&ascii), String::from_iter(SHADES));
+    }
+
+    #[test]
+    fn control_characters_are_kept() {
+        assert_eq!(encode_printable('☺'), None);
+        assert_eq!(encode_str("\x1b[31m░▒☺\n"), b"\x1b[31m\xb0\xb1?\n");
+    }
+}
+
+mod irc {
+    use super::cell;
+    use crate::color::ansi_24_bit::Ansi24Bit;
+    use crate::color::mirc::Mirc;
+    use crate::color::Color;
+    use crate::image::AsciiImage;
+    use crate::irc::IrcOptions;
+    use image::Rgb;
+
+    fn mirc(code: u8) -> Mirc {
+        Mirc::from_code(code).unwrap()
+    }
+
+    #[test]
+    fn colors_are_only_written_when_changed() {
+        let cells = [
+            cell('a', mirc(4), mirc(2)),
+            cell('b', mirc(4), mirc(2)),
+            cell('c', mirc(40), mirc(2)),
+            cell(',', mirc(50), mirc(2)),
+            cell('d', mirc(50), mirc(60)),
+        ];
+        let image = AsciiImage::from_cells(cells, 5, 1).unwrap();
+
+        assert_eq!(
+            image.to_irc(&IrcOptions::default()),
+            ["\x0304,02ab\x0340c\x0350,02,\x0350,60d"]
+        );
+    }
+
+    #[test]
+    fn long_rows_are_split() {
+        let image = AsciiImage::filled(10, 2, cell('#', mirc(4), mirc(2)));
+        let options = IrcOptions {
+            max_line_length: 10,
+            ..IrcOptions::default()
+        };
+
+        let messages = image.to_irc(&options);
+        assert_eq!(messages, ["\x0304,02####", "\x0304,02####", "\x0304,02##"].repeat(2));
+    }
+
+    #[test]
+    fn empty_image() {
+        assert!(AsciiImage::<Mirc>::new().to_irc(&IrcOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn classic_colors() {
+        let color = Ansi24Bit::new(255, 140, 0);
+        let image = AsciiImage::filled(1, 1, cell('#', color, color));
+        // Orange is 53 among the extended colors and 7 among the classic ones.
+        assert_eq!(image.to_irc(&IrcOptions::default()), ["\x0353,53#"]);
+
+        let options = IrcOptions {
+            extended_colors: false,
+            ..IrcOptions::default()
+        };
+        assert_eq!(image.to_irc(&options), ["\x0307,07#"]);
+        assert!(Mirc::classic_from_rgb(Rgb([255, 140, 0])).is_classic());
+        assert_eq!(Mirc::from_rgb(mirc(53).to_rgb()).code(), 53);
+    }
+}
+
+mod plain_text {
+    use super::{blank, cell};
+    use crate::cell::{AsciiCell, Attributes};
+    use crate::color::ansi_8_bit::Ansi8Bit;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+
+    #[test]
+    fn blank_cells_are_shaded() {
+        let background = |index| blank(Ansi8Bit::from_index(index));
+        let glyph = AsciiCell {
+            attributes: Attributes::BOLD,
+            ..cell('x', Ansi8Bit::from_index(16), Ansi8Bit::from_index(231))
+        };
+
+        // Black, gray, white and a character on white.
+        let cells = [background(16), background(244), background(231), glyph];
+        let image = AsciiImage::from_cells(cells, 2, 2).unwrap();
+        let font = Font::new_float([' ', '.', '+', '#'], 1.0, 0.5).unwrap();
+
+        assert_eq!(image.to_plain_text(&font), " +\n#x");
+    }
+
+    #[test]
+    fn reversed_spaces_are_shaded_by_their_foreground() {
+        let reversed = |character| AsciiCell {
+            attributes: Attributes {
+                reverse: true,
+                ..Attributes::NONE
+            },
+            ..cell(character, Ansi8Bit::from_index(231), Ansi8Bit::from_index(16))
+        };
+
+        let image = AsciiImage::from_cells([reversed(' '), reversed('x')], 2, 1).unwrap();
+        let font = Font::new_float([' ', '.', '+', '#'], 1.0, 0.5).unwrap();
+
+        assert_eq!(image.to_plain_text(&font), "#x");
+    }
+}
+
+#[cfg(feature = "terminal")]
+mod terminal {
+    use crate::color::colorless::Colorless;
+    use crate::conversion::Conversion;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+    use crate::sizing::Sizing;
+    use crate::terminal::TerminalSize;
+    use image::GrayImage;
+
+    #[test]
+    fn environment_variables() {
+        let size = TerminalSize { columns: 80, rows: 24 };
+
+        assert_eq!(TerminalSize::from_variables(Some("80"), Some("24")), Some(size));
+        assert_eq!(TerminalSize::from_variables(Some(" 80\n"), Some("24")), Some(size));
+        assert_eq!(TerminalSize::from_variables(None, Some("24")), None);
+        assert_eq!(TerminalSize::from_variables(Some("80"), None), None);
+        assert_eq!(TerminalSize::from_variables(Some("wide"), Some("24")), None);
+        assert_eq!(TerminalSize::from_variables(Some("-80"), Some("24")), None);
+        assert_eq!(TerminalSize::from_variables(Some("80"), Some("0")), None);
+    }
+
+    #[test]
+    fn reserved_rows() {
+        let size = TerminalSize { columns: 80, rows: 24 };
+        assert_eq!(size.reserving(2), TerminalSize { columns: 80, rows: 22 });
+
+        // Reserving every row leaves no room for the image.
+        let size = size.reserving(30);
+        assert_eq!(size.rows, 0);
+
+        let image = GrayImage::new(8, 8);
+        let font = Font::new_float([' ', '#'], 1.0, 0.5).unwrap();
+        let ascii = AsciiImage::<Colorless>::from_image_with_sizing(
+            &image,
+            &font,
+            &Conversion::default(),
+            &Sizing::Fit,
+            size.columns,
+            size.rows,
+        );
+        assert_eq!(ascii, AsciiImage::new());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resizes_are_noticed() {
+        let mut watcher = crate::terminal::ResizeWatcher::new().unwrap();
+        assert!(!watcher.resized());
+
+        signal_hook::low_level::raise(libc::SIGWINCH).unwrap();
+        assert!(watcher.resized());
+        assert!(!watcher.resized());
+    }
+}
+
+#[cfg(feature = "ratatui")]
+mod tui {
+    use super::renderer::Hashes;
+    use crate::cell::{AsciiCell, Attributes, Foreground};
+    use crate::color::ansi_3_bit::Ansi3Bit;
+    use crate::color::ansi_4_bit::Ansi4Bit;
+    use crate::color::ansi_8_bit::Ansi8Bit;
+    use crate::color::colorless::Colorless;
+    use crate::font::Font;
+    use crate::image::AsciiImage;
+    use crate::tui::{ImageState, ImageWidget};
+    use image::{Rgb, RgbImage};
+    use ratatui::buffer::Buffer;
+    use ratatui::layout::Rect;
+    use ratatui::style::{Color, Modifier};
+    use ratatui::widgets::{StatefulWidget, Widget};
+
+    #[test]
+    fn colors() {
+        assert_eq!(Color::from(Ansi4Bit::new_non_bright(Ansi3Bit::White)), Color::Gray);
+        assert_eq!(Color::from(Ansi4Bit::new_bright(Ansi3Bit::Black)), Color::DarkGray);
+        assert_eq!(Color::from(Ansi8Bit::from_index(202)), Color::Indexed(202));
+    }
+
+    #[test]
+    fn image_is_cut_off() {
+        let cell = AsciiCell {
+            background: Ansi4Bit::new_non_bright(Ansi3Bit::Blue),
+            foreground: Some(Foreground {
+                color: Ansi4Bit::new_bright(Ansi3Bit::Red),
+                character: '#',
+            }),
+            attributes: Attributes::BOLD,
+        };
+        let image = AsciiImage::filled(3, 3, cell);
+
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 4));
+        image.render(Rect::new(2, 1, 2, 5), &mut buffer);
+
+        let drawn = &buffer[(3, 2)];
+        assert_eq!(drawn.symbol(), "#");
+        assert_eq!(drawn.fg, Color::LightRed);
+        assert_eq!(drawn.bg, Color::Blue);
+        assert!(drawn.modifier.contains(Modifier::BOLD));
+
+        assert_eq!(buffer[(1, 1)].symbol(), " ");
+        assert_eq!(buffer[(2, 0)].bg, Color::Reset);
+    }
+
+    #[test]
+    fn conversion_is_kept_until_resized() {
+        let source = RgbImage::from_pixel(8, 8, Rgb([200, 30, 30]));
+        let font = Font::new_float(vec![' ', '.', '#'], 1.0, 0.5).unwrap();
+        let mut state = ImageState::<Ansi8Bit>::default();
+
+        let area = Rect::new(0, 0, 10, 3);
+        let mut buffer = Buffer::empty(area);
+        StatefulWidget::render(ImageWidget::new(&source, &font), area, &mut buffer, &mut state);
+        let converted = state.image().unwrap().clone();
+        assert_eq!((converted.width(), converted.height()), (6, 3));
+
+        StatefulWidget::render(ImageWidget::new(&source, &font), area, &mut buffer, &mut state);
+        assert_eq!(state.image(), Some(&converted));
+
+        let area = Rect::new(0, 0, 4, 3);
+        StatefulWidget::render(ImageWidget::new(&source, &font), area, &mut buffer, &mut state);
+        assert_eq!(state.image().unwrap().width(), 4);
+    }
+
+    #[test]
+    fn custom_renderer() {
+        let source = RgbImage::new(4, 4);
+        let font = Font::new_float(vec![' ', '#'], 1.0, 1.0).unwrap();
+
+        let area = Rect::new(0, 0, 2, 2);
+        let mut buffer = Buffer::empty(area);
+        let widget = ImageWidget::<_, _, Colorless>::new(&source, &font).with_renderer(Hashes);
+        Widget::render(widget, area, &mut buffer);
+
+        assert_eq!(buffer[(1, 1)].symbol(), "#");
+
+        let mut state = ImageState::default();
+        let widget = ImageWidget::<_, _, Colorless>::new(&source, &font).with_renderer(Hashes);
+        StatefulWidget::render(widget, area, &mut Buffer::empty(area), &mut state);
+
+        let converted = state.image().unwrap();
+        assert!(converted.rows().flatten().all(|cell| cell.foreground.unwrap().character == '#'));
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod wasm {
+    use crate::wasm::{render, Output, Palette, RenderError, RenderOptions, Rendered};
+
+    fn options(palette: Palette, columns: u32, output: Output) -> RenderOptions {
+        RenderOptions {
+            palette,
+            gradient: " #".to_string(),
+            columns,
+            aspect_ratio: 1.0,
+            output,
+        }
+    }
+
+    #[test]
+    fn cells() {
+        let rgba = [255, 255, 255, 255, 0, 0, 0, 255];
+
+        assert_eq!(
+            render(&rgba, 2, 1, &options(Palette::Ansi24Bit, 2, Output::Cells)),
+            Ok(Rendered::Cells(vec![32, 0xffffff, 0xffffff, 0, 32, 0, 0, 0])),
+        );
+    }
+
+    #[test]
+    fn buffers_may_be_longer() {
+        let options = options(Palette::Colorless, 1, Output::Ansi);
+
+        assert_eq!(render(&[0; 7], 2, 1, &options), Err(RenderError::InvalidBuffer));
+        assert!(render(&[0; 12], 2, 1, &options).is_ok());
+    }
+
+    #[test]
+    fn nothing_is_rendered_without_columns() {
+        let rgba = [0; 16];
+        let empty = Ok(Rendered::Cells(Vec::new()));
+
+        for output in [Output::Ansi, Output::Html] {
+            let options = options(Palette::Ansi8Bit, 0, output);
+            assert_eq!(render(&rgba, 2, 2, &options), Ok(Rendered::Text(String::new())));
+        }
+        assert_eq!(render(&rgba, 2, 2, &options(Palette::Ansi8Bit, 0, Output::Cells)), empty);
+        assert_eq!(render(&[], 0, 0, &options(Palette::Ansi8Bit, 4, Output::Cells)), empty);
+    }
+
+    #[test]
+    fn invalid_options() {
+        let mut options = options(Palette::Ansi4Bit, 1, Output::Ansi);
+        options.gradient.clear();
+
+        assert_eq!(render(&[0; 4], 1, 1, &options), Err(RenderError::InvalidFont));
+        assert_eq!("12-bit".parse::<Palette>(), Err(RenderError::UnknownPalette("12-bit".to_string())));
+        assert_eq!("svg".parse::<Output>(), Err(RenderError::UnknownOutput("svg".to_string())));
+        assert_eq!("24-bit".parse(), Ok(Palette::Ansi24Bit));
+    }
+}
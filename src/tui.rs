@@ -0,0 +1,284 @@
+//! Widgets for drawing ascii images in `ratatui` user interfaces.
+//!
+//! `AsciiImage` is a widget itself, drawn at the top left of the area it's rendered in.
+//! [`ImageWidget`] converts an image to the size of the area instead.
+
+use crate::cell::Attributes;
+use crate::color::ansi_24_bit::Ansi24Bit;
+use crate::color::ansi_3_bit::Ansi3Bit;
+use crate::color::ansi_4_bit::Ansi4Bit;
+use crate::color::ansi_8_bit::cube::Cube;
+use crate::color::ansi_8_bit::grayscale::Grayscale;
+use crate::color::ansi_8_bit::Ansi8Bit;
+use crate::color::colorless::Colorless;
+use crate::color::mirc::Mirc;
+use crate::color::Color;
+use crate::conversion::Conversion;
+use crate::font::Font;
+use crate::image::AsciiImage;
+use crate::parallel::{MaybeSend, MaybeSync};
+use crate::renderer::{CellRenderer, GradientMatcher};
+use crate::sizing::Sizing;
+use ::ratatui::buffer::Buffer;
+use ::ratatui::layout::Rect;
+use ::ratatui::style::{Color as TuiColor, Modifier, Style};
+use ::ratatui::widgets::{StatefulWidget, Widget};
+use image::{GenericImageView, Rgb};
+
+impl From<Ansi3Bit> for TuiColor {
+    fn from(color: Ansi3Bit) -> Self {
+        match color {
+            Ansi3Bit::Black => TuiColor::Black,
+            Ansi3Bit::Red => TuiColor::Red,
+            Ansi3Bit::Green => TuiColor::Green,
+            Ansi3Bit::Yellow => TuiColor::Yellow,
+            Ansi3Bit::Blue => TuiColor::Blue,
+            Ansi3Bit::Magenta => TuiColor::Magenta,
+            Ansi3Bit::Cyan => TuiColor::Cyan,
+            // Ratatui calls the non-bright white gray.
+            Ansi3Bit::White => TuiColor::Gray,
+        }
+    }
+}
+
+impl From<Ansi4Bit> for TuiColor {
+    fn from(color: Ansi4Bit) -> Self {
+        if !color.is_bright {
+            return color.color.into();
+        }
+
+        match color.color {
+            Ansi3Bit::Black => TuiColor::DarkGray,
+            Ansi3Bit::Red => TuiColor::LightRed,
+            Ansi3Bit::Green => TuiColor::LightGreen,
+            Ansi3Bit::Yellow => TuiColor::LightYellow,
+            Ansi3Bit::Blue => TuiColor::LightBlue,
+            Ansi3Bit::Magenta => TuiColor::LightMagenta,
+            Ansi3Bit::Cyan => TuiColor::LightCyan,
+            Ansi3Bit::White => TuiColor::White,
+        }
+    }
+}
+
+impl From<Ansi8Bit> for TuiColor {
+    fn from(color: Ansi8Bit) -> Self {
+        TuiColor::Indexed(color.index())
+    }
+}
+
+impl From<Cube> for TuiColor {
+    fn from(color: Cube) -> Self {
+        Ansi8Bit::Cube(color).into()
+    }
+}
+
+impl From<Grayscale> for TuiColor {
+    fn from(color: Grayscale) -> Self {
+        Ansi8Bit::Grayscale(color).into()
+    }
+}
+
+impl From<Ansi24Bit> for TuiColor {
+    fn from(color: Ansi24Bit) -> Self {
+        let Rgb([r, g, b]) = color.to_rgb();
+        TuiColor::Rgb(r, g, b)
+    }
+}
+
+impl From<Mirc> for TuiColor {
+    fn from(color: Mirc) -> Self {
+        Ansi24Bit::from(color.to_rgb()).into()
+    }
+}
+
+/// Uses the colors of the terminal.
+impl From<Colorless> for TuiColor {
+    fn from(_: Colorless) -> Self {
+        TuiColor::Reset
+    }
+}
+
+fn modifier(attributes: Attributes) -> Modifier {
+    let modifiers = [
+        (attributes.bold, Modifier::BOLD),
+        (attributes.dim, Modifier::DIM),
+        (attributes.italic, Modifier::ITALIC),
+        (attributes.underline, Modifier::UNDERLINED),
+        (attributes.reverse, Modifier::REVERSED),
+    ];
+
+    modifiers
+        .into_iter()
+        .filter(|(is_set, _)| *is_set)
+        .fold(Modifier::empty(), |all, (_, modifier)| all | modifier)
+}
+
+impl<C: Copy + Into<TuiColor>> Widget for &AsciiImage<C> {
+    /// Draws the image at the top left of `area`, cutting off what doesn't fit.
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+
+        for (y, row) in (area.y..area.bottom()).zip(self.rows()) {
+            for (x, cell) in (area.x..area.right()).zip(row) {
+                let Some(target) = buf.cell_mut((x, y)) else {
+                    continue;
+                };
+
+                let mut style = Style::new()
+                    .bg(cell.background.into())
+                    .add_modifier(modifier(cell.attributes));
+
+                if let Some(foreground) = cell.foreground {
+                    style = style.fg(foreground.color.into());
+                }
+
+                target.reset();
+                target
+                    .set_char(cell.foreground.map_or(' ', |foreground| foreground.character))
+                    .set_style(style);
+            }
+        }
+    }
+}
+
+impl<C: Copy + Into<TuiColor>> Widget for AsciiImage<C> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        (&self).render(area, buf);
+    }
+}
+
+/// Converts an image to the size of the area it's rendered in, as described by its sizing.
+///
+/// Rendered as a `Widget` the image is converted on every render.
+/// Rendered as a `StatefulWidget` the converted image is kept in an [`ImageState`]
+/// and only converted again when the size of the area changes.
+pub struct ImageWidget<'a, I, G, C, R = GradientMatcher> {
+    image: &'a I,
+    font: &'a Font<G>,
+    conversion: Conversion,
+    sizing: Sizing<C>,
+    renderer: R,
+}
+
+impl<'a, I, G, C> ImageWidget<'a, I, G, C> {
+    /// A widget which fits the image in the area using the default conversion.
+    #[must_use]
+    pub fn new(image: &'a I, font: &'a Font<G>) -> Self {
+        ImageWidget {
+            image,
+            font,
+            conversion: Conversion::default(),
+            sizing: Sizing::Fit,
+            renderer: GradientMatcher,
+        }
+    }
+}
+
+impl<'a, I, G, C, R> ImageWidget<'a, I, G, C, R> {
+    /// Sets the settings to convert the image with.
+    #[must_use]
+    pub fn with_conversion(mut self, conversion: Conversion) -> Self {
+        self.conversion = conversion;
+        self
+    }
+
+    /// Sets how the image is sized to the area.
+    /// With `Sizing::Fit` the image is drawn at the top left of the area.
+    #[must_use]
+    pub fn with_sizing(mut self, sizing: Sizing<C>) -> Self {
+        self.sizing = sizing;
+        self
+    }
+
+    /// Sets the renderer every cell is drawn with.
+    #[must_use]
+    pub fn with_renderer<T>(self, renderer: T) -> ImageWidget<'a, I, G, C, T> {
+        ImageWidget {
+            image: self.image,
+            font: self.font,
+            conversion: self.conversion,
+            sizing: self.sizing,
+            renderer,
+        }
+    }
+}
+
+impl<I, G, C, R> ImageWidget<'_, I, G, C, R>
+where
+    I: GenericImageView + MaybeSync,
+    G: AsRef<[char]> + MaybeSend + MaybeSync,
+    C: Color + MaybeSend,
+    R: CellRenderer<C> + MaybeSync,
+{
+    fn convert(&self, area: Rect) -> AsciiImage<C> {
+        AsciiImage::from_image_with_sizing_and_renderer(
+            self.image,
+            self.font,
+            &self.conversion,
+            &self.renderer,
+            &self.sizing,
+            area.width as u32,
+            area.height as u32,
+        )
+    }
+}
+
+impl<I, G, C, R> Widget for ImageWidget<'_, I, G, C, R>
+where
+    I: GenericImageView + MaybeSync,
+    G: AsRef<[char]> + MaybeSend + MaybeSync,
+    C: Color + MaybeSend + Into<TuiColor>,
+    R: CellRenderer<C> + MaybeSync,
+{
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.convert(area).render(area, buf);
+    }
+}
+
+/// The image converted by an [`ImageWidget`], along with the size it was converted to.
+#[derive(Clone, Debug)]
+pub struct ImageState<C> {
+    converted: Option<((u16, u16), AsciiImage<C>)>,
+}
+
+// Manually implemented since deriving would impose `C: Default`
+impl<C> Default for ImageState<C> {
+    fn default() -> Self {
+        ImageState { converted: None }
+    }
+}
+
+impl<C> ImageState<C> {
+    /// The image as it was last converted, if it has been rendered.
+    #[must_use]
+    pub fn image(&self) -> Option<&AsciiImage<C>> {
+        self.converted.as_ref().map(|(_, image)| image)
+    }
+
+    /// Forgets the converted image, so that it's converted again on the next render.
+    /// Needed when the source image or the settings of the widget change.
+    pub fn invalidate(&mut self) {
+        self.converted = None;
+    }
+}
+
+impl<I, G, C, R> StatefulWidget for ImageWidget<'_, I, G, C, R>
+where
+    I: GenericImageView + MaybeSync,
+    G: AsRef<[char]> + MaybeSend + MaybeSync,
+    C: Color + MaybeSend + Into<TuiColor>,
+    R: CellRenderer<C> + MaybeSync,
+{
+    type State = ImageState<C>;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let size = (area.width, area.height);
+
+        let image = match &mut state.converted {
+            Some((converted_size, image)) if *converted_size == size => image,
+            converted => &converted.insert((size, self.convert(area))).1,
+        };
+
+        image.render(area, buf);
+    }
+}
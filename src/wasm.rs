@@ -0,0 +1,239 @@
+//! A runtime-configured API for converting raw RGBA pixels,
+//! meant for WebAssembly and other foreign callers.
+//!
+//! On `wasm32` the functions are also exported to JavaScript using `wasm-bindgen`.
+//! Build with `--no-default-features --features wasm` to leave out rayon:
+//!
+//! ```sh
+//! cargo build --target wasm32-unknown-unknown --no-default-features --features wasm
+//! ```
+
+use crate::cell::AsciiCell;
+use crate::color::ansi_24_bit::Ansi24Bit;
+use crate::color::ansi_3_bit::Ansi3Bit;
+use crate::color::ansi_4_bit::Ansi4Bit;
+use crate::color::ansi_8_bit::Ansi8Bit;
+use crate::color::colorless::Colorless;
+use crate::color::Color;
+use crate::conversion::Conversion;
+use crate::font::Font;
+use crate::image::AsciiImage;
+use crate::renderer::{CellRenderer, GradientMatcher};
+use image::{ImageBuffer, Rgb, Rgba};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The number of `u32`s describing each cell in [`Rendered::Cells`].
+pub const CELL_STRIDE: usize = 4;
+
+/// The color type to convert to, chosen at runtime.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Palette {
+    Colorless,
+    Ansi3Bit,
+    Ansi4Bit,
+    Ansi8Bit,
+    Ansi24Bit,
+}
+
+impl FromStr for Palette {
+    type Err = RenderError;
+
+    /// Parses `"none"`, `"3-bit"`, `"4-bit"`, `"8-bit"` or `"24-bit"`.
+    fn from_str(palette: &str) -> Result<Self, Self::Err> {
+        match palette {
+            "none" => Ok(Palette::Colorless),
+            "3-bit" => Ok(Palette::Ansi3Bit),
+            "4-bit" => Ok(Palette::Ansi4Bit),
+            "8-bit" => Ok(Palette::Ansi8Bit),
+            "24-bit" => Ok(Palette::Ansi24Bit),
+            _ => Err(RenderError::UnknownPalette(palette.to_string())),
+        }
+    }
+}
+
+/// The format of the converted image.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Output {
+    /// Text with ansi escape codes.
+    Ansi,
+    /// HTML, as produced by [`AsciiImage::to_html`].
+    Html,
+    /// A flat array of cells, see [`Rendered::Cells`].
+    Cells,
+}
+
+impl FromStr for Output {
+    type Err = RenderError;
+
+    /// Parses `"ansi"`, `"html"` or `"cells"`.
+    fn from_str(output: &str) -> Result<Self, Self::Err> {
+        match output {
+            "ansi" => Ok(Output::Ansi),
+            "html" => Ok(Output::Html),
+            "cells" => Ok(Output::Cells),
+            _ => Err(RenderError::UnknownOutput(output.to_string())),
+        }
+    }
+}
+
+/// The converted image.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Rendered {
+    /// The image as ansi text or HTML.
+    Text(String),
+    /// [`CELL_STRIDE`] values per cell, going from left to right, wrapping from top to bottom:
+    /// the code point of the character, the foreground and background colors as `0xRRGGBB`,
+    /// and the attributes as given by [`Attributes::bits`](crate::cell::Attributes::bits).
+    /// Cells without a character use a space drawn in the background color.
+    Cells(Vec<u32>),
+}
+
+/// An error that occurred while rendering.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum RenderError {
+    /// The pixel buffer is shorter than `4 * width * height` bytes.
+    /// Bytes past the end of the image are ignored.
+    InvalidBuffer,
+    /// The gradient is empty or the aspect ratio isn't positive.
+    InvalidFont,
+    /// The palette couldn't be parsed.
+    UnknownPalette(String),
+    /// The output format couldn't be parsed.
+    UnknownOutput(String),
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::InvalidBuffer => write!(f, "the buffer is too short for the dimensions"),
+            RenderError::InvalidFont => write!(f, "invalid gradient or aspect ratio"),
+            RenderError::UnknownPalette(palette) => write!(f, "unknown palette {palette:?}"),
+            RenderError::UnknownOutput(output) => write!(f, "unknown output format {output:?}"),
+        }
+    }
+}
+
+impl Error for RenderError {}
+
+/// How to render the pixels.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RenderOptions {
+    pub palette: Palette,
+    /// A gradient of characters from least to most coverage.
+    pub gradient: String,
+    /// The width of the result in characters. The height follows from the aspect ratio.
+    /// With no columns the result is empty.
+    pub columns: u32,
+    /// The font width divided by the font height.
+    pub aspect_ratio: f32,
+    pub output: Output,
+}
+
+/// Converts an image given as RGBA bytes, row by row, to ascii.
+///
+/// # Errors
+/// If the buffer is too short for the dimensions or the options don't describe a valid font.
+pub fn render(rgba: &[u8], width: u32, height: u32, options: &RenderOptions) -> Result<Rendered, RenderError> {
+    let image = ImageBuffer::<Rgba<u8>, _>::from_raw(width, height, rgba).ok_or(RenderError::InvalidBuffer)?;
+
+    let gradient: Vec<char> = options.gradient.chars().collect();
+    let font = Font::new_float(gradient, 1.0, options.aspect_ratio).map_err(|_| RenderError::InvalidFont)?;
+
+    let rows = if width == 0 || options.columns == 0 {
+        0
+    } else {
+        (options.columns as f32 * height as f32 / width as f32 * options.aspect_ratio).round() as u32
+    };
+
+    Ok(match options.palette {
+        Palette::Colorless => render_with::<Colorless>(&image, &font, options.columns, rows, options.output),
+        Palette::Ansi3Bit => render_with::<Ansi3Bit>(&image, &font, options.columns, rows, options.output),
+        Palette::Ansi4Bit => render_with::<Ansi4Bit>(&image, &font, options.columns, rows, options.output),
+        Palette::Ansi8Bit => render_with::<Ansi8Bit>(&image, &font, options.columns, rows, options.output),
+        Palette::Ansi24Bit => render_with::<Ansi24Bit>(&image, &font, options.columns, rows, options.output),
+    })
+}
+
+fn render_with<C: Color + PartialEq>(
+    image: &ImageBuffer<Rgba<u8>, &[u8]>,
+    font: &Font<Vec<char>>,
+    columns: u32,
+    rows: u32,
+    output: Output,
+) -> Rendered
+where
+    GradientMatcher: CellRenderer<C>,
+{
+    let ascii = AsciiImage::<C>::from_image_with_conversion_serial(image, font, &Conversion::default(), columns, rows);
+
+    match output {
+        Output::Ansi => Rendered::Text(ascii.to_string()),
+        Output::Html => Rendered::Text(ascii.to_html()),
+        Output::Cells => Rendered::Cells(ascii.cells().iter().flat_map(encode_cell).collect()),
+    }
+}
+
+fn encode_cell<C: Color>(cell: &AsciiCell<C>) -> [u32; CELL_STRIDE] {
+    let rgb = |Rgb([r, g, b]): Rgb<u8>| u32::from_be_bytes([0, r, g, b]);
+
+    let background = cell.background.to_rgb();
+    let (character, foreground) = cell.foreground.map_or((' ', background), |foreground| {
+        (foreground.character, foreground.color.to_rgb())
+    });
+
+    [character as u32, rgb(foreground), rgb(background), cell.attributes.bits() as u32]
+}
+
+#[cfg(target_arch = "wasm32")]
+mod bindings {
+    use super::{render, Rendered, RenderOptions};
+    use wasm_bindgen::prelude::*;
+
+    fn options(palette: &str, gradient: &str, columns: u32, aspect_ratio: f32, output: &str) -> Result<RenderOptions, JsError> {
+        Ok(RenderOptions {
+            palette: palette.parse()?,
+            gradient: gradient.to_string(),
+            columns,
+            aspect_ratio,
+            output: output.parse()?,
+        })
+    }
+
+    /// Converts RGBA bytes to ansi text (`output = "ansi"`) or HTML (`output = "html"`).
+    #[wasm_bindgen(js_name = renderText)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_text(
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        palette: &str,
+        gradient: &str,
+        columns: u32,
+        aspect_ratio: f32,
+        output: &str,
+    ) -> Result<String, JsError> {
+        match render(rgba, width, height, &options(palette, gradient, columns, aspect_ratio, output)?)? {
+            Rendered::Text(text) => Ok(text),
+            Rendered::Cells(_) => Err(JsError::new("use `renderCells` for cell output")),
+        }
+    }
+
+    /// Converts RGBA bytes to a flat array of cells, see `Rendered::Cells`.
+    #[wasm_bindgen(js_name = renderCells)]
+    pub fn render_cells(
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        palette: &str,
+        gradient: &str,
+        columns: u32,
+        aspect_ratio: f32,
+    ) -> Result<Vec<u32>, JsError> {
+        match render(rgba, width, height, &options(palette, gradient, columns, aspect_ratio, "cells")?)? {
+            Rendered::Cells(cells) => Ok(cells),
+            Rendered::Text(_) => unreachable!("cells were requested"),
+        }
+    }
+}